@@ -1,35 +1,194 @@
-mod number;
+mod notation;
+mod rank;
 mod suit;
 
-pub(crate) use number::Number;
-pub(crate) use suit::Suit;
+pub use notation::{Notated, Notation};
+pub use rank::Rank;
+pub use suit::Suit;
 
-#[derive(Debug, Clone)]
-pub(crate) struct Card {
-    pub kind: Suit,
-    pub num: Number,
+use crate::ParseError;
+use std::{fmt, ops::RangeInclusive, str::FromStr};
+
+/// A single playing card.
+///
+/// Displays as rank then suit, e.g. `10H` or `QS`, or `JK` for a joker,
+/// see [`Card::display`] for other notations.
+/// Cards are ordered by rank and then by suit in bridge order; [`Card::cmp_rank`] ignores the suit.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Card {
+    rank: Rank,
+    suit: Suit,
 }
 
-impl From<&str> for Card {
-    fn from(value: &str) -> Self {
-        assert!(2 <= value.len(), "Too short");
-        let (num, kind) = value.split_at(value.len() - 1);
-        let card = kind.into();
+impl Card {
+    pub fn new(rank: Rank, suit: Suit) -> Self {
+        Self { rank, suit }
+    }
+
+    /// A joker, always wild. Its rank is meaningless.
+    pub fn joker() -> Self {
         Self {
-            kind: card,
-            num: num.into(),
+            rank: Rank::Ace,
+            suit: Suit::Joker,
+        }
+    }
+
+    pub fn is_joker(&self) -> bool {
+        self.suit == Suit::Joker
+    }
+
+    pub fn rank(&self) -> Rank {
+        self.rank
+    }
+
+    pub fn suit(&self) -> Suit {
+        self.suit
+    }
+
+    /// Write the card in another notation, e.g. `Th` or `T♥` rather than `10H`.
+    ///
+    /// ```
+    /// use poker::{Card, Notation};
+    ///
+    /// let card: Card = "Th".parse().unwrap();
+    /// assert_eq!(card.to_string(), "10H");
+    /// assert_eq!(card.display(Notation::Short).to_string(), "Th");
+    /// assert_eq!(card.display(Notation::Symbols).to_string(), "T♥");
+    /// assert_eq!(card.display(Notation::PlayingCard).to_string(), "🂺");
+    /// ```
+    pub fn display(&self, notation: Notation) -> Notated<'_, Card> {
+        Notated::new(self, notation)
+    }
+
+    /// Compare by rank alone, so that `AH` and `AS` are equal.
+    ///
+    /// ```
+    /// use poker::Card;
+    /// use std::cmp::Ordering;
+    ///
+    /// let ace_of_hearts: Card = "AH".parse().unwrap();
+    /// let ace_of_spades: Card = "AS".parse().unwrap();
+    /// assert_ne!(ace_of_hearts, ace_of_spades);
+    /// assert_eq!(ace_of_hearts.cmp_rank(&ace_of_spades), Ordering::Equal);
+    /// ```
+    pub fn cmp_rank(&self, other: &Self) -> std::cmp::Ordering {
+        self.rank.cmp(&other.rank)
+    }
+
+    /// Equal and not a joker: jokers are never the same card, since a deck may hold several of them.
+    pub(crate) fn is_same(&self, other: &Self) -> bool {
+        self == other && !self.is_joker()
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseError;
+
+    /// Rank followed by suit in any case, e.g. `10H`, `Th`, `T♥`, or a single glyph such as `🂺`.
+    ///
+    /// `JK`, `XX` and the joker glyphs read as a joker.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.eq_ignore_ascii_case("JK") || value.eq_ignore_ascii_case("XX") {
+            return Ok(Self::joker());
         }
+        if let Some(card) = notation::from_playing_card(value) {
+            return Ok(card);
+        }
+        let Some((split, _)) = value.char_indices().last() else {
+            return Err(ParseError::EmptyToken { offset: 0 });
+        };
+        let (rank, suit) = value.split_at(split);
+        let suit = suit.parse().map_err(|e: ParseError| e.shifted(split))?;
+        Ok(Self {
+            rank: rank.parse()?,
+            suit,
+        })
+    }
+}
+
+impl TryFrom<&str> for Card {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Parse space separated cards, e.g. `4S 5H 5S`, rejecting empty tokens and repeated cards.
+pub(crate) fn parse_cards(value: &str) -> Result<Vec<Card>, ParseError> {
+    let mut cards = Vec::<Card>::new();
+    let mut offset = 0;
+    for token in value.split(' ') {
+        if token.is_empty() {
+            return Err(ParseError::EmptyToken { offset });
+        }
+        let card: Card = token.parse().map_err(|e: ParseError| e.shifted(offset))?;
+        if cards.iter().any(|a| a.is_same(&card)) {
+            return Err(ParseError::DuplicateCard {
+                offset,
+                card: token.to_string(),
+            });
+        }
+        cards.push(card);
+        offset += token.len() + 1;
     }
+    Ok(cards)
 }
 
-impl PartialEq for Card {
-    fn eq(&self, other: &Self) -> bool {
-        self.num == other.num
+/// Parse `count` cards as [`parse_cards`] does, also rejecting any card already in `seen`.
+pub(crate) fn parse_unseen(
+    value: &str,
+    count: RangeInclusive<usize>,
+    seen: &[Card],
+) -> Result<Vec<Card>, ParseError> {
+    let cards = parse_cards(value)?;
+    ParseError::check_count(count, cards.len())?;
+    let mut offset = 0;
+    for (token, card) in value.split(' ').zip(&cards) {
+        if seen.iter().any(|a| a.is_same(card)) {
+            return Err(ParseError::DuplicateCard {
+                offset,
+                card: token.to_string(),
+            });
+        }
+        offset += token.len() + 1;
+    }
+    Ok(cards)
+}
+
+/// Fail on the first card that repeats an earlier one.
+pub(crate) fn check_duplicates(cards: &[Card]) -> Result<(), ParseError> {
+    match (1..cards.len()).find(|&i| cards[..i].iter().any(|a| a.is_same(&cards[i]))) {
+        Some(i) => Err(ParseError::DuplicateCard {
+            offset: written_offset(cards, i),
+            card: cards[i].to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Fail on the first joker, for games played without wild cards; `value` is the text the
+/// cards were parsed from.
+pub(crate) fn check_no_jokers(value: &str, cards: &[Card]) -> Result<(), ParseError> {
+    match cards.iter().position(Card::is_joker) {
+        Some(i) => Err(ParseError::Joker {
+            offset: value.split(' ').take(i).map(|a| a.len() + 1).sum(),
+        }),
+        None => Ok(()),
     }
 }
 
-impl PartialOrd for Card {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.num.partial_cmp(&other.num)
+/// Byte offset of `cards[i]` in the cards written out separated by spaces, the offset errors
+/// about cards that did not come from a string report.
+pub(crate) fn written_offset(cards: &[Card], i: usize) -> usize {
+    cards[..i].iter().map(|a| a.to_string().len() + 1).sum()
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_joker() {
+            return f.write_str("JK");
+        }
+        write!(f, "{}{}", self.rank, self.suit)
     }
 }
@@ -0,0 +1,136 @@
+use super::{Card, Rank, Suit};
+use std::fmt;
+
+/// A way of writing cards, see [`Card::display`].
+///
+/// Parsing accepts all of them, mixed freely.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Notation {
+    /// `10H`, `QS` and `JK` for a joker, the same as `Display`.
+    #[default]
+    Standard,
+    /// `Th`, `Qs`, as written by most hand histories and solvers.
+    Short,
+    /// `T♥`, `Q♠` and `🃏` for a joker.
+    Symbols,
+    /// One glyph from the Unicode playing-card block, e.g. `🂺` or `🂭`.
+    ///
+    /// Ranks and suits on their own have no such glyph and are written as in [`Notation::Symbols`].
+    PlayingCard,
+}
+
+/// A rank, suit, card or list of cards written in a given [`Notation`].
+#[derive(Debug, Clone, Copy)]
+pub struct Notated<'a, T: ?Sized> {
+    value: &'a T,
+    notation: Notation,
+}
+
+impl<'a, T: ?Sized> Notated<'a, T> {
+    pub(crate) fn new(value: &'a T, notation: Notation) -> Self {
+        Self { value, notation }
+    }
+}
+
+impl fmt::Display for Notated<'_, Rank> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.notation, self.value) {
+            (Notation::Standard, a) => write!(f, "{a}"),
+            (_, Rank::Ten) => f.write_str("T"),
+            (_, a) => write!(f, "{a}"),
+        }
+    }
+}
+
+impl fmt::Display for Notated<'_, Suit> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbols = ["♣", "♦", "♥", "♠", "*"];
+        match self.notation {
+            Notation::Standard => write!(f, "{}", self.value),
+            Notation::Short => write!(f, "{}", self.value.to_string().to_ascii_lowercase()),
+            Notation::Symbols | Notation::PlayingCard => f.write_str(symbols[*self.value as usize]),
+        }
+    }
+}
+
+impl fmt::Display for Notated<'_, Card> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let card = self.value;
+        match self.notation {
+            Notation::Standard => write!(f, "{card}"),
+            Notation::Short if card.is_joker() => f.write_str("JK"),
+            _ if card.is_joker() => f.write_str("🃏"),
+            Notation::PlayingCard => write!(f, "{}", to_playing_card(card)),
+            notation => write!(
+                f,
+                "{}{}",
+                card.rank.display(notation),
+                card.suit.display(notation)
+            ),
+        }
+    }
+}
+
+/// Cards separated by spaces, except in [`Notation::PlayingCard`] where glyphs follow each other.
+impl fmt::Display for Notated<'_, [Card]> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = match self.notation {
+            Notation::PlayingCard => "",
+            _ => " ",
+        };
+        for (i, card) in self.value.iter().enumerate() {
+            if i > 0 {
+                f.write_str(separator)?;
+            }
+            write!(f, "{}", card.display(self.notation))?;
+        }
+        Ok(())
+    }
+}
+
+/// Offset of the ace of each suit in the playing-card block, which has a knight between
+/// the jack and the queen.
+const PLAYING_CARD_SUITS: [(Suit, u32); 4] = [
+    (Suit::Spade, 0x1F0A0),
+    (Suit::Heart, 0x1F0B0),
+    (Suit::Diamond, 0x1F0C0),
+    (Suit::Club, 0x1F0D0),
+];
+
+fn to_playing_card(card: &Card) -> char {
+    let base = PLAYING_CARD_SUITS
+        .iter()
+        .find(|a| a.0 == card.suit)
+        .unwrap()
+        .1;
+    let offset = match card.rank {
+        Rank::Ace => 1,
+        Rank::Queen | Rank::King => card.rank.value() as u32 + 1,
+        a => a.value() as u32,
+    };
+    char::from_u32(base + offset).unwrap()
+}
+
+/// The card written as one glyph of the playing-card block, if `value` is one.
+///
+/// The three jokers of the block are all read as [`Card::joker`]; knights are not a card here.
+pub(crate) fn from_playing_card(value: &str) -> Option<Card> {
+    let mut chars = value.chars();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    let c = c as u32;
+    if matches!(c, 0x1F0BF | 0x1F0CF | 0x1F0DF) {
+        return Some(Card::joker());
+    }
+    let (suit, base) = PLAYING_CARD_SUITS
+        .into_iter()
+        .find(|a| (a.1 + 1..=a.1 + 14).contains(&c))?;
+    let rank = match c - base {
+        1 => Rank::Ace,
+        12 => return None,
+        a @ 13..=14 => Rank::from_value(a as u8 - 1)?,
+        a => Rank::from_value(a as u8)?,
+    };
+    Some(Card::new(rank, suit))
+}
@@ -0,0 +1,131 @@
+use super::{Notated, Notation};
+use crate::ParseError;
+use std::{fmt, ops::Add, str::FromStr};
+
+/// Rank of a card, ordered ace-high.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rank {
+    Two = 2,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    /// Every rank, lowest first.
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    /// `2` for a deuce up to `14` for an ace.
+    pub fn value(self) -> u8 {
+        self as u8
+    }
+
+    /// Like [`Rank::value`], but with the ace counting as `1`, as in low hands.
+    pub fn low_value(self) -> u8 {
+        match self {
+            Rank::Ace => 1,
+            a => a.value(),
+        }
+    }
+
+    /// Inverse of [`Rank::value`]; `1` is also accepted as an ace.
+    pub fn from_value(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(Rank::Ace),
+            2..=14 => Some(Self::ALL[value as usize - 2]),
+            _ => None,
+        }
+    }
+
+    /// Lowercase English name, e.g. `"six"`.
+    pub fn name(self) -> &'static str {
+        const NAMES: [&str; 13] = [
+            "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "jack",
+            "queen", "king", "ace",
+        ];
+        NAMES[self as usize - 2]
+    }
+
+    /// Lowercase English plural, e.g. `"sixes"`.
+    pub fn plural(self) -> String {
+        match self {
+            Rank::Six => "sixes".to_string(),
+            a => format!("{}s", a.name()),
+        }
+    }
+
+    /// Write the rank in another notation, e.g. `T` rather than `10`.
+    pub fn display(&self, notation: Notation) -> Notated<'_, Rank> {
+        Notated::new(self, notation)
+    }
+
+    fn symbol(self) -> &'static str {
+        const SYMBOLS: [&str; 13] = [
+            "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K", "A",
+        ];
+        SYMBOLS[self as usize - 2]
+    }
+}
+
+impl FromStr for Rank {
+    type Err = ParseError;
+
+    /// `2` to `10` or `T`, then `J Q K A`, in any case.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|a| a.symbol().eq_ignore_ascii_case(value))
+            .or((value.eq_ignore_ascii_case("T")).then_some(Rank::Ten))
+            .ok_or_else(|| ParseError::BadRank {
+                offset: 0,
+                rank: value.to_string(),
+            })
+    }
+}
+
+impl TryFrom<&str> for Rank {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+/// Steps up the ranks, wrapping from king to ace and from ace to two.
+impl Add<u8> for Rank {
+    type Output = Rank;
+
+    fn add(self, rhs: u8) -> Self::Output {
+        let index = (self as u8 - 2 + rhs % 13) % 13;
+        Self::ALL[index as usize]
+    }
+}
@@ -1,20 +1,69 @@
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub(crate) enum Suit {
-    Heart,
+use super::{Notated, Notation};
+use crate::ParseError;
+use std::{fmt, str::FromStr};
+
+/// Suit of a card, in bridge order (clubs lowest, spades highest).
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Suit {
+    Club,
     Diamond,
-    Clover,
+    Heart,
     Spade,
-    _Joker,
+    /// The "suit" of a joker, which has no real rank or suit.
+    Joker,
+}
+
+impl Suit {
+    /// The four regular suits, lowest first.
+    pub const ALL: [Suit; 4] = [Suit::Club, Suit::Diamond, Suit::Heart, Suit::Spade];
+
+    /// Write the suit in another notation, e.g. `h` or `♥` rather than `H`.
+    pub fn display(&self, notation: Notation) -> Notated<'_, Suit> {
+        Notated::new(self, notation)
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Suit::Club => "C",
+            Suit::Diamond => "D",
+            Suit::Heart => "H",
+            Suit::Spade => "S",
+            Suit::Joker => "*",
+        }
+    }
 }
 
-impl From<&str> for Suit {
-    fn from(value: &str) -> Self {
+impl FromStr for Suit {
+    type Err = ParseError;
+
+    /// `C D H S` in any case, or the symbols `♣ ♦ ♥ ♠` and their outlined forms `♧ ♢ ♡ ♤`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
         match value {
-            "H" => Suit::Heart,
-            "D" => Suit::Diamond,
-            "C" => Suit::Clover,
-            "S" => Suit::Spade,
-            a => panic!("Unrecognizable kind: {a}"),
+            "♣" | "♧" => Some(Suit::Club),
+            "♦" | "♢" => Some(Suit::Diamond),
+            "♥" | "♡" => Some(Suit::Heart),
+            "♠" | "♤" => Some(Suit::Spade),
+            _ => Self::ALL
+                .into_iter()
+                .find(|a| a.symbol().eq_ignore_ascii_case(value)),
         }
+        .ok_or_else(|| ParseError::BadSuit {
+            offset: 0,
+            suit: value.to_string(),
+        })
+    }
+}
+
+impl TryFrom<&str> for Suit {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
     }
 }
@@ -0,0 +1,203 @@
+//! A deck of cards with reproducible shuffles.
+
+use crate::{card::parse_cards, Card, DeckError, ParseError, Rank, Suit};
+use itertools::Itertools;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::{fmt, str::FromStr};
+
+/// Cards in order, the top of the deck first.
+///
+/// The order displays as space separated cards and parses back,
+/// so a disputed deal can be replayed exactly from the logged deck:
+///
+/// ```
+/// use poker::deck::Deck;
+///
+/// let mut deck = Deck::new();
+/// deck.shuffle_seeded(42);
+/// let logged = deck.to_string();
+///
+/// let mut replay: Deck = logged.parse().unwrap();
+/// assert_eq!(replay.deal(2).unwrap(), deck.deal(2).unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Deck {
+    cards: Vec<Card>,
+}
+
+impl Deck {
+    /// The 52 standard cards, unshuffled.
+    pub fn new() -> Self {
+        Self::from_ranks(&Rank::ALL)
+    }
+
+    /// The 36 cards of a short deck, six through ace, unshuffled.
+    pub fn short() -> Self {
+        Self::from_ranks(&Rank::ALL[Rank::Six as usize - 2..])
+    }
+
+    fn from_ranks(ranks: &[Rank]) -> Self {
+        Self {
+            cards: Suit::ALL
+                .into_iter()
+                .cartesian_product(ranks)
+                .map(|(suit, &rank)| Card::new(rank, suit))
+                .collect(),
+        }
+    }
+
+    /// Add `count` jokers to the bottom of the deck.
+    pub fn with_jokers(mut self, count: usize) -> Self {
+        self.cards.extend((0..count).map(|_| Card::joker()));
+        self
+    }
+
+    /// Remaining cards, the top of the deck first.
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Shuffle the remaining cards with `rng`.
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.cards.shuffle(rng);
+    }
+
+    /// Shuffle with a ChaCha8 generator seeded from `seed`,
+    /// so the same seed gives the same order on every platform.
+    pub fn shuffle_seeded(&mut self, seed: u64) {
+        self.shuffle_with(&mut ChaCha8Rng::seed_from_u64(seed));
+    }
+
+    /// Take `n` cards from the top; the deck is unchanged if fewer remain.
+    pub fn deal(&mut self, n: usize) -> Result<Vec<Card>, DeckError> {
+        if n > self.cards.len() {
+            return Err(DeckError::NotEnoughCards {
+                requested: n,
+                remaining: self.cards.len(),
+            });
+        }
+        Ok(self.cards.drain(..n).collect())
+    }
+
+    /// Discard the top card.
+    pub fn burn(&mut self) -> Result<Card, DeckError> {
+        self.deal(1).map(|a| a[0])
+    }
+
+    /// Take out dead cards, wherever they are in the deck.
+    ///
+    /// Fails without removing anything if any of them is not in the deck.
+    pub fn remove(&mut self, cards: &[Card]) -> Result<(), DeckError> {
+        let mut remaining = self.cards.clone();
+        for card in cards {
+            let i = remaining
+                .iter()
+                .position(|a| a.is_same(card) || a.is_joker() && card.is_joker())
+                .ok_or(DeckError::MissingCard(*card))?;
+            remaining.remove(i);
+        }
+        self.cards = remaining;
+        Ok(())
+    }
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromStr for Deck {
+    type Err = ParseError;
+
+    /// Space separated cards, the top of the deck first.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value.is_empty() {
+            return Ok(Self { cards: vec![] });
+        }
+        Ok(Self {
+            cards: parse_cards(value)?,
+        })
+    }
+}
+
+impl fmt::Display for Deck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.cards.iter().join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn same(a: &[Card], b: &[Card]) -> bool {
+        a.len() == b.len()
+            && a.iter()
+                .zip(b)
+                .all(|(a, b)| a.is_same(b) || a.is_joker() && b.is_joker())
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(Deck::new().len(), 52);
+        assert_eq!(Deck::new().with_jokers(2).len(), 54);
+        let short = Deck::short();
+        assert_eq!(short.len(), 36);
+        assert!(short.cards().iter().all(|a| a.rank() >= Rank::Six));
+    }
+
+    #[test]
+    fn seeded_shuffle_is_reproducible() {
+        let mut a = Deck::new();
+        let mut b = Deck::new();
+        a.shuffle_seeded(7);
+        b.shuffle_seeded(7);
+        assert!(same(a.cards(), b.cards()));
+        b.shuffle_seeded(8);
+        assert!(!same(a.cards(), b.cards()));
+    }
+
+    #[test]
+    fn deal_burn_remove() {
+        let mut deck = Deck::new();
+        deck.shuffle_seeded(1);
+        let top = deck.cards()[..3].to_vec();
+        assert!(same(&deck.deal(2).unwrap(), &top[..2]));
+        assert!(deck.burn().unwrap().is_same(&top[2]));
+        assert_eq!(deck.len(), 49);
+        assert_eq!(
+            deck.deal(50),
+            Err(DeckError::NotEnoughCards {
+                requested: 50,
+                remaining: 49
+            })
+        );
+
+        let dead = deck.cards()[10];
+        deck.remove(&[dead]).unwrap();
+        assert_eq!(deck.len(), 48);
+        assert!(matches!(
+            deck.remove(&[top[0]]),
+            Err(DeckError::MissingCard(a)) if a.is_same(&top[0])
+        ));
+        assert_eq!(deck.len(), 48);
+    }
+
+    #[test]
+    fn order_round_trips() {
+        let mut deck = Deck::new().with_jokers(1);
+        deck.shuffle_seeded(3);
+        let replay: Deck = deck.to_string().parse().unwrap();
+        assert!(same(deck.cards(), replay.cards()));
+    }
+}
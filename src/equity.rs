@@ -0,0 +1,642 @@
+//! Hold'em equity: how often each player wins given their hole cards and a partial board.
+//!
+//! Small problems are enumerated exhaustively; larger ones are sampled with a seeded
+//! Monte Carlo run, reporting the standard error of each estimate.
+
+use crate::{
+    deck::Deck,
+    lookup::{self, CompactCard},
+    range::Range,
+    Card, ParseError,
+};
+use itertools::Itertools;
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    Rng, SeedableRng,
+};
+use rand_chacha::ChaCha8Rng;
+
+/// How to run an equity calculation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    /// Enumerate every runout when there are at most this many of them.
+    pub max_exhaustive: u64,
+    /// Number of random runouts otherwise; zero is an error when it comes to sampling.
+    pub samples: u64,
+    /// Seed of the Monte Carlo generator, so runs are reproducible.
+    pub seed: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            max_exhaustive: 100_000,
+            samples: 100_000,
+            seed: 0,
+        }
+    }
+}
+
+/// Outcome for one player, each as a fraction of all runouts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerEquity {
+    /// Won the whole pot.
+    pub win: f64,
+    /// Split the pot with at least one other player.
+    pub tie: f64,
+    /// Won nothing.
+    pub loss: f64,
+    /// Expected share of the pot, counting a two-way tie as half a win and so on.
+    pub equity: f64,
+    /// Standard error of `equity`; zero when enumerated exhaustively.
+    pub std_error: f64,
+}
+
+/// Result of [`calculate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Equity {
+    /// One entry per player, in input order.
+    pub players: Vec<PlayerEquity>,
+    /// Number of runouts evaluated. A range sample can stop short of [`Settings::samples`],
+    /// see [`calculate_ranges`].
+    pub trials: u64,
+    /// Whether every runout was evaluated, rather than a random sample.
+    pub exhaustive: bool,
+}
+
+impl Equity {
+    /// Largest standard error over all players, a quick convergence check.
+    pub fn max_std_error(&self) -> f64 {
+        self.players.iter().map(|a| a.std_error).fold(0.0, f64::max)
+    }
+}
+
+/// Equity of each player holding two `holes` cards, given 0 to 5 `board` cards
+/// and `dead` cards known to be out of play.
+///
+/// At least one player is needed, else the error is [`ParseError::NoPlayers`]. When the deck
+/// cannot deal the rest of the board the error is [`ParseError::NotEnoughCards`], and when the
+/// runouts must be sampled but [`Settings::samples`] is zero it is [`ParseError::NoSamples`].
+///
+/// ```
+/// use poker::equity::{calculate, Settings};
+///
+/// let cards = |a: &str| a.split(' ').map(|a| a.parse().unwrap()).collect::<Vec<_>>();
+/// let equity = calculate(
+///     &[cards("AH KH"), cards("QS QD")],
+///     &cards("QH 7H 2C"),
+///     &[],
+///     &Settings::default(),
+/// )
+/// .unwrap();
+/// assert!(equity.exhaustive);
+/// assert!(equity.players[1].equity > 0.6);
+/// ```
+pub fn calculate(
+    holes: &[Vec<Card>],
+    board: &[Card],
+    dead: &[Card],
+    settings: &Settings,
+) -> Result<Equity, ParseError> {
+    if holes.is_empty() {
+        return Err(ParseError::NoPlayers);
+    }
+    let known = validate(holes, board, dead)?;
+    let compact = |cards: &[Card]| -> Vec<CompactCard> {
+        cards.iter().map(|&a| a.try_into().unwrap()).collect()
+    };
+    let holes = holes.iter().map(|a| compact(a)).collect::<Vec<_>>();
+    let board = compact(board);
+    let mut deck = Deck::new();
+    deck.remove(&known).expect("validated distinct cards");
+    let deck = compact(deck.cards());
+
+    let missing = 5 - board.len();
+    check_deal(2 * holes.len() + missing, 2 * holes.len() + deck.len())?;
+    let runouts = count_combinations(deck.len() as u64, missing as u64);
+    check_samples(runouts <= settings.max_exhaustive, settings)?;
+    let mut tally = Tally::new(holes.len());
+    if runouts <= settings.max_exhaustive {
+        for runout in deck.iter().copied().combinations(missing) {
+            tally.add(&holes, &board, &runout, 1.0);
+        }
+    } else {
+        let mut rng = ChaCha8Rng::seed_from_u64(settings.seed);
+        let mut deck = deck;
+        for _ in 0..settings.samples {
+            // partial Fisher-Yates: the first `missing` cards become a uniform sample
+            for i in 0..missing {
+                let j = rng.gen_range(i..deck.len());
+                deck.swap(i, j);
+            }
+            tally.add(&holes, &board, &deck[..missing], 1.0);
+        }
+    }
+    Ok(tally.finish(runouts <= settings.max_exhaustive))
+}
+
+/// Equity of each player holding a hand from their [`Range`], given 0 to 5 `board` cards
+/// and `dead` cards known to be out of play.
+///
+/// A known hand plays as a one-combo range such as `AhKh`, giving hand vs range equity.
+/// Combos sharing a card with the board, the dead cards or each other are never dealt.
+/// Exhaustive enumeration weighs each deal by the product of its combos' weights;
+/// Monte Carlo sampling draws combos in proportion to their weights.
+///
+/// When the ranges share so many cards that 1000 draws in a row deal some card twice, sampling
+/// stops there and [`Equity::trials`] is lower than [`Settings::samples`]; if not a single deal
+/// could be made the error is [`ParseError::EmptyRange`] for the last range. With no ranges at
+/// all the error is [`ParseError::NoPlayers`]; the other errors are those of [`calculate`].
+///
+/// ```
+/// use poker::equity::{calculate_ranges, Settings};
+///
+/// let ranges = ["AhKh".parse().unwrap(), "QQ+, AKs".parse().unwrap()];
+/// let board = ["QH", "7H", "2C"].map(|a| a.parse().unwrap());
+/// let equity = calculate_ranges(&ranges, &board, &[], &Settings::default()).unwrap();
+/// assert!(equity.exhaustive);
+/// assert!(equity.players[0].equity < 0.5);
+/// ```
+pub fn calculate_ranges(
+    ranges: &[Range],
+    board: &[Card],
+    dead: &[Card],
+    settings: &Settings,
+) -> Result<Equity, ParseError> {
+    if ranges.is_empty() {
+        return Err(ParseError::NoPlayers);
+    }
+    let known = validate(&[], board, dead)?;
+    let ranges = ranges
+        .iter()
+        .enumerate()
+        .map(|(i, range)| {
+            let combos = range
+                .without(&known)
+                .combos()
+                .iter()
+                .filter(|a| a.weight > 0.0)
+                .map(|a| (a.cards.map(|a| CompactCard::try_from(a).unwrap()), a.weight))
+                .collect::<Vec<_>>();
+            match combos.is_empty() {
+                true => Err(ParseError::EmptyRange.in_hand(i)),
+                false => Ok(combos),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let board = board
+        .iter()
+        .map(|&a| CompactCard::try_from(a).unwrap())
+        .collect::<Vec<_>>();
+    let mut deck = Deck::new();
+    deck.remove(&known).expect("validated distinct cards");
+    let deck = deck
+        .cards()
+        .iter()
+        .map(|&a| CompactCard::try_from(a).unwrap())
+        .collect::<Vec<_>>();
+
+    let missing = 5 - board.len();
+    check_deal(2 * ranges.len() + missing, deck.len())?;
+    let runouts = count_combinations((deck.len() - 2 * ranges.len()) as u64, missing as u64);
+    let deals = ranges
+        .iter()
+        .fold(1u64, |acc, a| acc.saturating_mul(a.len() as u64));
+    let exhaustive = deals.saturating_mul(runouts) <= settings.max_exhaustive;
+    check_samples(exhaustive, settings)?;
+    let unused = |holes: &[[CompactCard; 2]]| -> Vec<CompactCard> {
+        deck.iter()
+            .filter(|a| !holes.iter().flatten().contains(a))
+            .copied()
+            .collect()
+    };
+
+    let mut tally = Tally::new(ranges.len());
+    if exhaustive {
+        for deal in ranges.iter().map(|a| a.iter()).multi_cartesian_product() {
+            let holes = deal.iter().map(|a| a.0).collect::<Vec<_>>();
+            if !holes.iter().flatten().all_unique() {
+                continue;
+            }
+            let weight = deal.iter().map(|a| a.1).product();
+            for runout in unused(&holes).into_iter().combinations(missing) {
+                tally.add(&holes, &board, &runout, weight);
+            }
+        }
+    } else {
+        let mut rng = ChaCha8Rng::seed_from_u64(settings.seed);
+        let choosers = ranges
+            .iter()
+            .map(|a| WeightedIndex::new(a.iter().map(|a| a.1)).unwrap())
+            .collect::<Vec<_>>();
+        for _ in 0..settings.samples {
+            let Some(holes) = (0..1000)
+                .map(|_| {
+                    choosers
+                        .iter()
+                        .zip(&ranges)
+                        .map(|(a, range)| range[a.sample(&mut rng)].0)
+                        .collect::<Vec<_>>()
+                })
+                .find(|a| a.iter().flatten().all_unique())
+            else {
+                break;
+            };
+            let mut rest = unused(&holes);
+            for i in 0..missing {
+                let j = rng.gen_range(i..rest.len());
+                rest.swap(i, j);
+            }
+            tally.add(&holes, &board, &rest[..missing], 1.0);
+        }
+    }
+    if tally.trials == 0 {
+        // every deal has two players sharing a card
+        return Err(ParseError::EmptyRange.in_hand(ranges.len() - 1));
+    }
+    Ok(tally.finish(exhaustive))
+}
+
+/// Check counts, that no card appears twice and that there are no jokers; returns every
+/// known card. Offsets are those of the cards written out separated by spaces.
+pub(crate) fn validate(
+    holes: &[Vec<Card>],
+    board: &[Card],
+    dead: &[Card],
+) -> Result<Vec<Card>, ParseError> {
+    ParseError::check_count(0..=5, board.len()).map_err(ParseError::in_board)?;
+    let mut known = Vec::<Card>::new();
+    let mut add = |cards: &[Card]| -> Result<(), ParseError> {
+        let mut offset = 0;
+        for card in cards {
+            if card.is_joker() {
+                return Err(ParseError::Joker { offset });
+            }
+            if known.contains(card) {
+                return Err(ParseError::DuplicateCard {
+                    offset,
+                    card: card.to_string(),
+                });
+            }
+            known.push(*card);
+            offset += card.to_string().len() + 1;
+        }
+        Ok(())
+    };
+    add(board).map_err(ParseError::in_board)?;
+    for (i, hole) in holes.iter().enumerate() {
+        ParseError::check_count(2..=2, hole.len())
+            .and_then(|_| add(hole))
+            .map_err(|e| e.in_hand(i))?;
+    }
+    add(dead)?;
+    Ok(known)
+}
+
+/// Fail unless the `left` cards, those neither on the board nor dead, can deal `needed`.
+fn check_deal(needed: usize, left: usize) -> Result<(), ParseError> {
+    match needed <= left {
+        true => Ok(()),
+        false => Err(ParseError::NotEnoughCards { needed, left }),
+    }
+}
+
+/// Fail when the runouts must be sampled but there are no samples to take.
+fn check_samples(exhaustive: bool, settings: &Settings) -> Result<(), ParseError> {
+    match exhaustive || settings.samples > 0 {
+        true => Ok(()),
+        false => Err(ParseError::NoSamples),
+    }
+}
+
+pub(crate) fn count_combinations(n: u64, k: u64) -> u64 {
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+/// Running totals over runouts, each counted with a weight.
+pub(crate) struct Tally {
+    wins: Vec<f64>,
+    ties: Vec<f64>,
+    shares: Vec<f64>,
+    squares: Vec<f64>,
+    weight: f64,
+    trials: u64,
+}
+
+impl Tally {
+    pub(crate) fn new(players: usize) -> Self {
+        Self {
+            wins: vec![0.0; players],
+            ties: vec![0.0; players],
+            shares: vec![0.0; players],
+            squares: vec![0.0; players],
+            weight: 0.0,
+            trials: 0,
+        }
+    }
+
+    /// Record one runout completing `board`.
+    pub(crate) fn add(
+        &mut self,
+        holes: &[impl AsRef<[CompactCard]>],
+        board: &[CompactCard],
+        runout: &[CompactCard],
+        weight: f64,
+    ) {
+        let mut cards = [board, runout].concat();
+        let strengths = holes
+            .iter()
+            .map(|hole| {
+                cards.truncate(5);
+                cards.extend(hole.as_ref());
+                lookup::best_strength(&cards)
+            })
+            .collect::<Vec<_>>();
+        let best = *strengths.iter().max().unwrap();
+        let winners = strengths.iter().filter(|&&a| a == best).count();
+        for (i, &strength) in strengths.iter().enumerate() {
+            if strength != best {
+                continue;
+            }
+            let share = 1.0 / winners as f64;
+            if winners == 1 {
+                self.wins[i] += weight;
+            } else {
+                self.ties[i] += weight;
+            }
+            self.shares[i] += weight * share;
+            self.squares[i] += weight * share * share;
+        }
+        self.weight += weight;
+        self.trials += 1;
+    }
+
+    pub(crate) fn finish(self, exhaustive: bool) -> Equity {
+        let n = self.weight;
+        let players = (0..self.wins.len())
+            .map(|i| {
+                let equity = self.shares[i] / n;
+                let variance = (self.squares[i] / n - equity * equity).max(0.0);
+                PlayerEquity {
+                    win: self.wins[i] / n,
+                    tie: self.ties[i] / n,
+                    loss: (n - self.wins[i] - self.ties[i]) / n,
+                    equity,
+                    std_error: if exhaustive {
+                        0.0
+                    } else {
+                        (variance / self.trials as f64).sqrt()
+                    },
+                }
+            })
+            .collect();
+        Equity {
+            players,
+            trials: self.trials,
+            exhaustive,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+
+    fn cards(value: &str) -> Vec<Card> {
+        parse_cards(value).unwrap()
+    }
+
+    #[test]
+    fn complete_board() {
+        let equity = calculate(
+            &[cards("AH KH"), cards("QS QD")],
+            &cards("QH 7H 2C 3H 9S"),
+            &[],
+            &Settings::default(),
+        )
+        .unwrap();
+        assert_eq!(equity.trials, 1);
+        assert_eq!(equity.players[0].win, 1.0);
+        assert_eq!(equity.players[1].loss, 1.0);
+    }
+
+    #[test]
+    fn turn_enumerates_every_river() {
+        // 9 spades make the flush, but 4S and 9S also fill up the set of kings
+        let equity = calculate(
+            &[cards("AS 2S"), cards("KD KC")],
+            &cards("KS 7S 4D 9H"),
+            &[],
+            &Settings::default(),
+        )
+        .unwrap();
+        assert!(equity.exhaustive);
+        assert_eq!(equity.trials, 44);
+        assert_eq!(equity.players[0].win, 7.0 / 44.0);
+
+        // with one more spade dead there are only 6 outs among 43 rivers
+        let equity = calculate(
+            &[cards("AS 2S"), cards("KD KC")],
+            &cards("KS 7S 4D 9H"),
+            &cards("3S"),
+            &Settings::default(),
+        )
+        .unwrap();
+        assert_eq!(equity.trials, 43);
+        assert_eq!(equity.players[0].win, 6.0 / 43.0);
+    }
+
+    #[test]
+    fn chopped_board() {
+        let equity = calculate(
+            &[cards("2C 3D"), cards("2D 3C")],
+            &cards("10H JH QS KD AC"),
+            &[],
+            &Settings::default(),
+        )
+        .unwrap();
+        assert_eq!(equity.players[0].tie, 1.0);
+        assert_eq!(equity.players[0].equity, 0.5);
+    }
+
+    #[test]
+    fn monte_carlo_preflop() {
+        let settings = Settings {
+            samples: 20_000,
+            seed: 9,
+            ..Settings::default()
+        };
+        let holes = [cards("AH AD"), cards("KS KC")];
+        let equity = calculate(&holes, &[], &[], &settings).unwrap();
+        assert!(!equity.exhaustive);
+        assert_eq!(equity.trials, 20_000);
+        // aces are about an 82% favourite
+        let error = equity.players[0].std_error;
+        assert!(error > 0.0 && error < 0.01);
+        assert!((equity.players[0].equity - 0.82).abs() < 4.0 * error + 0.005);
+        assert_eq!(equity, calculate(&holes, &[], &[], &settings).unwrap());
+    }
+
+    #[test]
+    fn hand_versus_range() {
+        // AhKh against KK+ on a queen-high flop, with the hand blocking the range
+        let ranges = ["AhKh".parse().unwrap(), "KK+".parse().unwrap()];
+        let equity =
+            calculate_ranges(&ranges, &cards("QH 7H 2C"), &[], &Settings::default()).unwrap();
+        assert!(equity.exhaustive);
+        // AhKh blocks combos: 3 kings and 3 aces make 3 + 3 pairs
+        assert_eq!(equity.trials, 6 * 990);
+
+        let single = calculate_ranges(
+            &["AhKh".parse().unwrap(), "KsKd".parse().unwrap()],
+            &cards("QH 7H 2C"),
+            &[],
+            &Settings::default(),
+        )
+        .unwrap();
+        let exact = calculate(
+            &[cards("AH KH"), cards("KS KD")],
+            &cards("QH 7H 2C"),
+            &[],
+            &Settings::default(),
+        )
+        .unwrap();
+        assert_eq!(single, exact);
+    }
+
+    #[test]
+    fn range_versus_range_weights() {
+        let settings = Settings {
+            samples: 20_000,
+            seed: 5,
+            ..Settings::default()
+        };
+        // with KK weighted to nothing, AA is only ever against QQ
+        let ranges: [Range; 2] = ["AA".parse().unwrap(), "KK:0, QQ".parse().unwrap()];
+        let weighted = calculate_ranges(&ranges, &[], &[], &settings).unwrap();
+        let ranges: [Range; 2] = ["AA".parse().unwrap(), "QQ".parse().unwrap()];
+        let plain = calculate_ranges(&ranges, &[], &[], &settings).unwrap();
+        assert!(!weighted.exhaustive);
+        assert_eq!(weighted, plain);
+        assert!((weighted.players[0].equity - 0.81).abs() < 0.02);
+    }
+
+    #[test]
+    fn range_with_nothing_left() {
+        let ranges = ["AA".parse().unwrap(), "AhKh".parse().unwrap()];
+        assert_eq!(
+            calculate_ranges(&ranges, &cards("KH 7D 2C"), &[], &Settings::default()),
+            Err(ParseError::EmptyRange.in_hand(1))
+        );
+    }
+
+    #[test]
+    fn needs_a_player() {
+        assert_eq!(
+            calculate(&[], &cards("QH 7H 2C"), &[], &Settings::default()),
+            Err(ParseError::NoPlayers)
+        );
+        let equity = calculate(&[cards("2C 3D")], &[], &[], &Settings::default()).unwrap();
+        assert_eq!(equity.players[0].win, 1.0);
+    }
+
+    #[test]
+    fn deck_must_cover_the_deal() {
+        // 24 players leave four cards for a five-card board
+        let holes = Deck::new()
+            .cards()
+            .chunks(2)
+            .take(24)
+            .map(<[Card]>::to_vec)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            calculate(&holes, &[], &[], &Settings::default()),
+            Err(ParseError::NotEnoughCards {
+                needed: 53,
+                left: 52
+            })
+        );
+        let ranges = vec!["22+".parse().unwrap(); 24];
+        assert_eq!(
+            calculate_ranges(&ranges, &cards("2C 3D"), &[], &Settings::default()),
+            Err(ParseError::NotEnoughCards {
+                needed: 51,
+                left: 50
+            })
+        );
+    }
+
+    #[test]
+    fn sampling_needs_samples() {
+        let settings = Settings {
+            samples: 0,
+            ..Settings::default()
+        };
+        let holes = [cards("AH KH"), cards("QS QD")];
+        assert_eq!(
+            calculate(&holes, &[], &[], &settings),
+            Err(ParseError::NoSamples)
+        );
+        let ranges = ["AKs".parse().unwrap(), "QQ".parse().unwrap()];
+        assert_eq!(
+            calculate_ranges(&ranges, &[], &[], &settings),
+            Err(ParseError::NoSamples)
+        );
+        // enumeration does without
+        assert!(calculate(&holes, &cards("QH 7H 2C"), &[], &settings).is_ok());
+    }
+
+    #[test]
+    fn colliding_ranges_stop_sampling() {
+        assert_eq!(
+            calculate_ranges(&[], &[], &[], &Settings::default()),
+            Err(ParseError::NoPlayers)
+        );
+        // three players cannot each hold two of the four aces
+        let ranges = ["AA", "AA", "AA"].map(|a| a.parse().unwrap());
+        assert_eq!(
+            calculate_ranges(&ranges, &[], &[], &Settings::default()),
+            Err(ParseError::EmptyRange.in_hand(2))
+        );
+        // two players: 6 × 6 deals of which only 6 are disjoint, about one in six is kept
+        let settings = Settings {
+            samples: 1000,
+            ..Settings::default()
+        };
+        let ranges = ["AA", "AA"].map(|a| a.parse().unwrap());
+        let equity = calculate_ranges(&ranges, &[], &[], &settings).unwrap();
+        assert_eq!(equity.trials, 1000);
+        assert!((equity.players[0].equity - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn rejects_jokers() {
+        let board = parse_cards("2C JK 3D").unwrap();
+        assert_eq!(
+            calculate(&[cards("AH KH")], &board, &[], &Settings::default()),
+            Err(ParseError::Joker { offset: 3 }.in_board())
+        );
+        assert_eq!(
+            ParseError::Joker { offset: 3 }.to_string(),
+            "joker at byte 3 in a game without wild cards"
+        );
+    }
+
+    #[test]
+    fn rejects_repeated_cards() {
+        assert_eq!(
+            calculate(
+                &[cards("AH KH"), cards("AH QD")],
+                &[],
+                &[],
+                &Settings::default()
+            ),
+            Err(ParseError::DuplicateCard {
+                offset: 0,
+                card: "AH".to_string()
+            }
+            .in_hand(1))
+        );
+    }
+}
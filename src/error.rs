@@ -0,0 +1,326 @@
+use crate::Card;
+use std::{fmt, ops::RangeInclusive};
+
+/// Why a card or hand string could not be parsed.
+///
+/// Every `offset` is a byte offset into the string that was handed to the parser,
+/// so `&input[offset..]` starts at the offending token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The rank part of a card is not one of `A 2 3 4 5 6 7 8 9 10 T J Q K`, in any case.
+    BadRank { offset: usize, rank: String },
+    /// The suit part of a card is not one of `H D C S`, in any case, or a suit symbol.
+    BadSuit { offset: usize, suit: String },
+    /// A hand did not contain the expected number of cards.
+    WrongCardCount {
+        expected: RangeInclusive<usize>,
+        found: usize,
+    },
+    /// The same card appears twice in one hand.
+    DuplicateCard { offset: usize, card: String },
+    /// A joker where the game is played without wild cards.
+    Joker { offset: usize },
+    /// Two separators in a row, or a separator at either end of the input.
+    EmptyToken { offset: usize },
+    /// One of several input hands was invalid; `index` is its position in the input.
+    InvalidHand {
+        index: usize,
+        error: Box<ParseError>,
+    },
+    /// The shared community cards were invalid.
+    InvalidBoard { error: Box<ParseError> },
+    /// An entry of a hand range, such as `AKs` or `22-66`, is malformed.
+    BadRange { offset: usize, entry: String },
+    /// A hand range has no combination left once the known cards are removed.
+    EmptyRange,
+    /// No hands or ranges were given to play against each other.
+    NoPlayers,
+    /// The deck runs out before every player and the rest of the board is dealt.
+    NotEnoughCards { needed: usize, left: usize },
+    /// A Monte Carlo run was asked for with zero samples.
+    NoSamples,
+}
+
+impl ParseError {
+    /// Move every offset `by` bytes to the right,
+    /// used when a token is parsed on its own and then placed back into the whole input.
+    pub(crate) fn shifted(self, by: usize) -> Self {
+        use ParseError::*;
+        match self {
+            BadRank { offset, rank } => BadRank {
+                offset: offset + by,
+                rank,
+            },
+            BadSuit { offset, suit } => BadSuit {
+                offset: offset + by,
+                suit,
+            },
+            DuplicateCard { offset, card } => DuplicateCard {
+                offset: offset + by,
+                card,
+            },
+            EmptyToken { offset } => EmptyToken {
+                offset: offset + by,
+            },
+            Joker { offset } => Joker {
+                offset: offset + by,
+            },
+            BadRange { offset, entry } => BadRange {
+                offset: offset + by,
+                entry,
+            },
+            a @ (WrongCardCount { .. }
+            | InvalidHand { .. }
+            | InvalidBoard { .. }
+            | EmptyRange
+            | NoPlayers
+            | NotEnoughCards { .. }
+            | NoSamples) => a,
+        }
+    }
+
+    /// Tag the error with the position of the hand it came from.
+    pub(crate) fn in_hand(self, index: usize) -> Self {
+        ParseError::InvalidHand {
+            index,
+            error: Box::new(self),
+        }
+    }
+
+    /// Tag the error as coming from the board.
+    pub(crate) fn in_board(self) -> Self {
+        ParseError::InvalidBoard {
+            error: Box::new(self),
+        }
+    }
+
+    /// Fail unless `found` lies in `expected`.
+    pub(crate) fn check_count(
+        expected: RangeInclusive<usize>,
+        found: usize,
+    ) -> Result<(), ParseError> {
+        if expected.contains(&found) {
+            Ok(())
+        } else {
+            Err(ParseError::WrongCardCount { expected, found })
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ParseError::*;
+        match self {
+            BadRank { offset, rank } => write!(f, "unrecognizable rank {rank:?} at byte {offset}"),
+            BadSuit { offset, suit } => write!(f, "unrecognizable suit {suit:?} at byte {offset}"),
+            WrongCardCount { expected, found } if expected.start() == expected.end() => {
+                write!(f, "expected {} cards, found {found}", expected.start())
+            }
+            WrongCardCount { expected, found } => write!(
+                f,
+                "expected {} to {} cards, found {found}",
+                expected.start(),
+                expected.end()
+            ),
+            DuplicateCard { offset, card } => write!(f, "duplicate card {card} at byte {offset}"),
+            EmptyToken { offset } => write!(f, "empty card at byte {offset}"),
+            Joker { offset } => write!(f, "joker at byte {offset} in a game without wild cards"),
+            InvalidHand { index, error } => write!(f, "hand #{index}: {error}"),
+            InvalidBoard { error } => write!(f, "board: {error}"),
+            BadRange { offset, entry } => write!(f, "malformed range {entry:?} at byte {offset}"),
+            EmptyRange => write!(f, "no combination left in range"),
+            NoPlayers => write!(f, "no players"),
+            NotEnoughCards { needed, left } => {
+                write!(f, "the deal needs {needed} cards but only {left} are left")
+            }
+            NoSamples => write!(f, "zero Monte Carlo samples"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::InvalidHand { error, .. } | ParseError::InvalidBoard { error } => {
+                Some(error.as_ref())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Why a [`crate::deck::Deck`] operation could not be carried out.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeckError {
+    /// More cards were requested than remain in the deck.
+    NotEnoughCards { requested: usize, remaining: usize },
+    /// A card to remove is not in the deck, e.g. it was already dealt.
+    MissingCard(Card),
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeckError::NotEnoughCards {
+                requested,
+                remaining,
+            } => write!(f, "requested {requested} cards, only {remaining} remain"),
+            DeckError::MissingCard(card) => write!(f, "{card} is not in the deck"),
+        }
+    }
+}
+
+impl std::error::Error for DeckError {}
+
+/// Why the pots could not be paid out by [`crate::pot::award`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PotError {
+    /// Nobody is eligible for pot number `pot`, e.g. because everyone who paid into it folded.
+    NobodyEligible { pot: usize },
+}
+
+impl fmt::Display for PotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PotError::NobodyEligible { pot } => write!(f, "nobody is eligible for pot #{pot}"),
+        }
+    }
+}
+
+impl std::error::Error for PotError {}
+
+/// Why a hand could not be started by [`crate::holdem::game::State::new`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SetupError {
+    /// A hand needs at least two players.
+    TooFewPlayers { players: usize },
+    /// The player in `seat` has no chips.
+    EmptyStack { seat: usize },
+    /// The button is not one of the `players` seats.
+    BadButton { button: usize, players: usize },
+    /// The deck holds jokers, but Hold'em is played without wild cards.
+    Jokers,
+    /// The deck cannot deal the hand.
+    Deck(DeckError),
+}
+
+impl From<DeckError> for SetupError {
+    fn from(error: DeckError) -> Self {
+        SetupError::Deck(error)
+    }
+}
+
+impl fmt::Display for SetupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use SetupError::*;
+        match self {
+            TooFewPlayers { players } => write!(f, "{players} players, a hand needs at least two"),
+            EmptyStack { seat } => write!(f, "the player in seat {seat} has no chips"),
+            BadButton { button, players } => {
+                write!(f, "button {button} is not a seat at a table of {players}")
+            }
+            Jokers => write!(f, "the deck holds jokers"),
+            Deck(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for SetupError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SetupError::Deck(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Why an action was refused by [`crate::holdem::game::State::apply`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IllegalAction {
+    /// The hand is over, nobody is left to act.
+    HandComplete,
+    /// There is a bet of `to_call` chips to call, fold or raise.
+    CannotCheck { to_call: u64 },
+    /// There is no bet to call.
+    NothingToCall,
+    /// There is already a bet this street, raise it instead.
+    AlreadyBet,
+    /// There is no bet to raise, bet instead.
+    NothingToRaise,
+    /// The player may not raise: only an incomplete all-in raise was made since they acted,
+    /// or nobody else can still put chips in.
+    RaiseNotAllowed,
+    /// Bets and raises must bring the player's total for the street to at least `min`.
+    AmountTooSmall { min: u64 },
+    /// The player only has enough chips to bring their total for the street to `max`.
+    AmountTooLarge { max: u64 },
+}
+
+impl fmt::Display for IllegalAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use IllegalAction::*;
+        match self {
+            HandComplete => write!(f, "the hand is complete"),
+            CannotCheck { to_call } => write!(f, "cannot check facing a bet of {to_call}"),
+            NothingToCall => write!(f, "there is no bet to call"),
+            AlreadyBet => write!(f, "there is already a bet, raise instead"),
+            NothingToRaise => write!(f, "there is no bet to raise, bet instead"),
+            RaiseNotAllowed => write!(f, "raising is not allowed"),
+            AmountTooSmall { min } => write!(f, "must bet or raise to at least {min}"),
+            AmountTooLarge { max } => write!(f, "cannot bet or raise to more than {max}"),
+        }
+    }
+}
+
+impl std::error::Error for IllegalAction {}
+
+/// Why a hand history could not be read or does not add up.
+///
+/// Line numbers count from 1 at the first line of the hand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryError {
+    /// A line the hand cannot do without, such as the header or the table line, is missing.
+    MissingLine { expected: &'static str },
+    /// A line has an unexpected shape.
+    BadLine { line: usize, text: String },
+    /// Cards in brackets on a line are malformed.
+    BadCards { line: usize, error: ParseError },
+    /// An action names a player who has no seat.
+    UnknownPlayer { line: usize, name: String },
+    /// The amounts a player put in do not add up, e.g. an uncalled bet larger than their bet.
+    BadAmount { player: String },
+    /// Every player folded, leaving nobody to win the pot.
+    EveryoneFolded,
+    /// The players who collected chips are not those the evaluator finds.
+    WrongWinners {
+        reported: Vec<String>,
+        expected: Vec<String>,
+    },
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use HistoryError::*;
+        match self {
+            MissingLine { expected } => write!(f, "missing {expected} line"),
+            BadLine { line, text } => write!(f, "line {line}: cannot read {text:?}"),
+            BadCards { line, error } => write!(f, "line {line}: {error}"),
+            UnknownPlayer { line, name } => write!(f, "line {line}: no player called {name:?}"),
+            BadAmount { player } => write!(f, "the amounts {player:?} put in do not add up"),
+            EveryoneFolded => write!(f, "every player folded"),
+            WrongWinners { reported, expected } => write!(
+                f,
+                "reported winners {reported:?}, but the hands shown make {expected:?} win"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HistoryError::BadCards { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
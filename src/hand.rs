@@ -1,28 +1,82 @@
 mod hands;
+mod value;
 
-use hands::Hands;
+pub use hands::Hands;
+pub use value::HandValue;
 
-use crate::{Card, Number};
+use crate::{
+    card::{check_duplicates, parse_cards, Notated, Notation},
+    wild::{self, Wilds},
+    Card, ParseError, Rank,
+};
 use itertools::Itertools;
-use std::cell::RefCell;
+use std::{cell::RefCell, str::FromStr};
 
-#[derive(Debug, PartialEq)]
-pub(crate) struct Hand(Vec<Card>, RefCell<Option<(Hands, Vec<Number>)>>);
+/// Five cards, compared by their [`HandValue`], so hands of the same value are equal
+/// whatever their suits.
+#[derive(Debug, Clone)]
+pub struct Hand(Vec<Card>, RefCell<Option<HandValue>>);
 
 impl Hand {
-    fn hand(&self) -> (Hands, Vec<Number>) {
+    /// Build a hand from exactly five distinct cards.
+    pub fn new(cards: Vec<Card>) -> Result<Self, ParseError> {
+        ParseError::check_count(Self::SIZE..=Self::SIZE, cards.len())?;
+        check_duplicates(&cards)?;
+        Ok(Self::from_cards(cards))
+    }
+
+    /// Build a hand from cards already known to be valid.
+    pub(crate) fn from_cards(cards: Vec<Card>) -> Self {
+        Self(cards, RefCell::new(None))
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.0
+    }
+
+    /// The cards written in the given notation, separated by spaces.
+    pub fn display(&self, notation: Notation) -> Notated<'_, [Card]> {
+        Notated::new(self.cards(), notation)
+    }
+
+    /// Category and tie-break ranks of this hand, computed once and cached.
+    ///
+    /// Jokers are wild, see [`Wilds`] to make other cards wild too.
+    pub fn evaluate(&self) -> HandValue {
         if let Some(hand) = self.1.borrow().clone() {
             return hand;
         }
+        let ret = if self.0.iter().any(Card::is_joker) {
+            wild::evaluate(&self.0, &Wilds::default())
+        } else {
+            self.compute(true)
+        };
+        *self.1.borrow_mut() = Some(ret.clone());
+        ret
+    }
+
+    /// Plain English description, see [`HandValue::describe`].
+    pub fn describe(&self) -> String {
+        self.evaluate().describe()
+    }
+
+    /// Evaluate without the cache and without wild cards;
+    /// `wheel` tells whether A-2-3-4-5 counts as a straight.
+    pub(crate) fn compute(&self, wheel: bool) -> HandValue {
+        self.compute_below(wheel.then_some(Rank::Two))
+    }
 
+    /// Like [`Hand::compute`], the ace also playing low below `lowest`, the lowest rank of the
+    /// deck: `Six` makes A-6-7-8-9 a straight, as in short deck.
+    pub(crate) fn compute_below(&self, lowest: Option<Rank>) -> HandValue {
         let res = (
             self.is_flush(),
-            self.is_straight(),
+            self.is_straight(lowest),
             self.count_same_number_combination(),
         );
-        let def = vec![Number(2)];
 
-        let ret = match res {
+        let (category, ranks) = match res {
+            (_, _, (10, nums)) => (Hands::FiveOfAKind, nums),
             (Some(_), Some(num), _) => (Hands::StraightFlush, num),
             (None, Some(num), _) => (Hands::Straight, num),
             (Some(num), None, _) => (Hands::Flush, num),
@@ -31,29 +85,35 @@ impl Hand {
             (_, _, (3, nums)) => (Hands::ThreeOfAKind, nums),
             (_, _, (2, nums)) => (Hands::TwoPair, nums),
             (_, _, (1, nums)) => (Hands::OnePair, nums),
-            _ => (Hands::HighCard, def),
+            (_, _, (_, nums)) => (Hands::HighCard, nums),
         };
-        *self.1.borrow_mut() = Some(ret.clone());
-        ret
+        HandValue::new(category, ranks)
     }
 
-    fn is_flush(&self) -> Option<Vec<Number>> {
+    /// Straights and flushes need five cards.
+    fn is_flush(&self) -> Option<Vec<Rank>> {
+        if self.0.len() < Self::SIZE {
+            return None;
+        }
         self.0
             .windows(2)
-            .all(|pair| pair[0].kind == pair[1].kind)
+            .all(|pair| pair[0].suit() == pair[1].suit())
             .then(|| {
-                let mut nums = self.0.iter().map(|a| a.num).collect::<Vec<_>>();
+                let mut nums = self.0.iter().map(|a| a.rank()).collect::<Vec<_>>();
                 nums.sort();
                 nums.reverse();
                 nums
             })
     }
 
-    fn is_straight(&self) -> Option<Vec<Number>> {
-        let mut tmp = self.0.iter().map(|a| a.num).collect::<Vec<_>>();
+    fn is_straight(&self, lowest: Option<Rank>) -> Option<Vec<Rank>> {
+        if self.0.len() < Self::SIZE {
+            return None;
+        }
+        let mut tmp = self.0.iter().map(|a| a.rank()).collect::<Vec<_>>();
         tmp.sort();
 
-        let _is_straight = |tmp: &mut [Number]| {
+        let _is_straight = |tmp: &mut [Rank]| {
             tmp.windows(2)
                 .map(|a| (a[0], a[1]))
                 .all(|(a, b)| a + 1 == b)
@@ -67,27 +127,29 @@ impl Hand {
             // aces can start a straight (A 2 3 4 5)
             .or_else(|| {
                 let ace = tmp.pop().unwrap();
-                if ace == Number(1) {
-                    tmp.insert(0, ace);
-                    _is_straight(&mut tmp)
+                if ace == Rank::Ace && lowest == Some(tmp[0]) {
+                    let mut ranks = _is_straight(&mut tmp)?;
+                    ranks.push(ace);
+                    Some(ranks)
                 } else {
                     None
                 }
             })
     }
 
+    /// 10 => Five of a kind
     /// 6 => Four of a kind
     /// 4 => Full House
     /// 3 => Three of a kind
     /// 2 => Two pair
     /// 1 => One pair
-    /// compare with `Vec<Number>`
+    /// compare with `Vec<Rank>`
     /// first element is more important to rank
-    fn count_same_number_combination(&self) -> (i32, Vec<Number>) {
+    fn count_same_number_combination(&self) -> (i32, Vec<Rank>) {
         let duped = self
             .0
             .iter()
-            .map(|a| a.num)
+            .map(|a| a.rank())
             .combinations(2)
             .filter_map(|pair| (pair[0] == pair[1]).then_some(pair[0]))
             .collect::<Vec<_>>();
@@ -99,7 +161,6 @@ impl Hand {
             // both hands have a full house, tie goes to highest-ranked triplet
             cnv.sort_by_key(|a| a.0); // secondary key
             cnv.sort_by_key(|a| a.1); // primary key
-            println!("cnv: {cnv:?}");
             cnv
         };
         let mut nums = counted.into_iter().map(|a| a.0).rev().collect::<Vec<_>>();
@@ -107,7 +168,7 @@ impl Hand {
         let mut remained = self
             .0
             .iter()
-            .map(|a| a.num)
+            .map(|a| a.rank())
             .filter(|a| !nums.contains(a))
             .collect::<Vec<_>>();
         remained.sort();
@@ -117,35 +178,85 @@ impl Hand {
     }
 }
 
-impl From<&str> for Hand {
-    fn from(value: &str) -> Self {
-        let cards: _ = value.split(' ').map(|a| a.into()).collect();
-        Self(cards, RefCell::new(None))
+impl Hand {
+    pub(crate) const SIZE: usize = 5;
+}
+
+impl FromStr for Hand {
+    type Err = ParseError;
+
+    /// Space separated cards, e.g. `4S 5H 5S 5D 5C`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let cards = parse_cards(value)?;
+        ParseError::check_count(Self::SIZE..=Self::SIZE, cards.len())?;
+        Ok(Self::from_cards(cards))
+    }
+}
+
+impl TryFrom<&str> for Hand {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Value of one to five cards, for a hand still being dealt, such as a stud board or
+/// hole cards and a flop.
+///
+/// Fewer than five cards can only make pairs, two pair, three or four of a kind, never a
+/// straight or a flush; five cards are evaluated as a [`Hand`]. Jokers are wild.
+///
+/// ```
+/// use poker::{evaluate_partial, Card, Hands};
+///
+/// let cards = "KS KD 4C".split(' ').map(|a| a.parse().unwrap()).collect::<Vec<Card>>();
+/// let value = evaluate_partial(&cards).unwrap();
+/// assert_eq!(value.category(), Hands::OnePair);
+/// assert_eq!(value.describe(), "Pair of kings");
+/// ```
+pub fn evaluate_partial(cards: &[Card]) -> Result<HandValue, ParseError> {
+    ParseError::check_count(1..=Hand::SIZE, cards.len())?;
+    check_duplicates(cards)?;
+    Ok(Hand::from_cards(cards.to_vec()).evaluate())
+}
+
+/// Compare two hands, explaining which category, card or kicker decided the outcome.
+///
+/// ```
+/// use poker::{compare_explain, Hand};
+/// use std::cmp::Ordering;
+///
+/// let a: Hand = "KS KD 4C 4H QS".parse().unwrap();
+/// let b: Hand = "KH KC 4D 4S JS".parse().unwrap();
+/// assert_eq!(
+///     compare_explain(&a, &b),
+///     (
+///         Ordering::Greater,
+///         "both have two pair, kings and fours; decided by kicker Q vs J".to_string()
+///     )
+/// );
+/// ```
+pub fn compare_explain(a: &Hand, b: &Hand) -> (std::cmp::Ordering, String) {
+    a.evaluate().compare_explain(&b.evaluate())
+}
+
+impl PartialEq for Hand {
+    fn eq(&self, other: &Self) -> bool {
+        self.evaluate() == other.evaluate()
     }
 }
 
+impl Eq for Hand {}
+
 impl PartialOrd for Hand {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        let sh = self.hand();
-        let oh = other.hand();
-        if sh.0 == oh.0 {
-            use Hands::*;
-            match sh.0 {
-                _FiveOfAKind => Some(std::cmp::Ordering::Equal),
-                StraightFlush | FourOfAKind | FullHouse | Flush | Straight | ThreeOfAKind
-                | TwoPair | OnePair => sh.1.partial_cmp(&oh.1),
-                HighCard => {
-                    let mut sn = self.0.clone();
-                    let mut on = other.0.clone();
-                    sn.sort_by_key(|a| a.num);
-                    on.sort_by_key(|a| a.num);
-                    sn.reverse();
-                    on.reverse();
-                    sn.partial_cmp(&on)
-                }
-            }
-        } else {
-            sh.0.partial_cmp(&oh.0)
-        }
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.evaluate().cmp(&other.evaluate())
     }
 }
@@ -1,7 +1,17 @@
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub(crate) enum Hands {
-    // opt
-    _FiveOfAKind = 10,
+use std::fmt;
+
+/// Category of a poker hand, from best to worst.
+///
+/// With the `serde` feature it serializes in snake case, e.g. `"full_house"`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Hands {
+    /// Only reachable with wild cards.
+    FiveOfAKind = 10,
     StraightFlush = 9,
     FourOfAKind = 8,
     FullHouse = 7,
@@ -12,3 +22,21 @@ pub(crate) enum Hands {
     OnePair = 2,
     HighCard = 1,
 }
+
+impl fmt::Display for Hands {
+    /// Lowercase name of the category, e.g. `"full house"`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Hands::FiveOfAKind => "five of a kind",
+            Hands::StraightFlush => "straight flush",
+            Hands::FourOfAKind => "four of a kind",
+            Hands::FullHouse => "full house",
+            Hands::Flush => "flush",
+            Hands::Straight => "straight",
+            Hands::ThreeOfAKind => "three of a kind",
+            Hands::TwoPair => "two pair",
+            Hands::OnePair => "one pair",
+            Hands::HighCard => "high card",
+        })
+    }
+}
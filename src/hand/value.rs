@@ -0,0 +1,147 @@
+use super::Hands;
+use crate::Rank;
+use std::cmp::Ordering;
+
+/// Strength of an evaluated hand.
+///
+/// Compares by category first, then by the tie-break ranks,
+/// so two values are equal exactly when the hands split the pot.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "crate::serialize::RawHandValue")
+)]
+pub struct HandValue {
+    category: Hands,
+    ranks: Vec<Rank>,
+}
+
+impl HandValue {
+    pub(crate) fn new(category: Hands, ranks: Vec<Rank>) -> Self {
+        Self { category, ranks }
+    }
+
+    pub fn category(&self) -> Hands {
+        self.category
+    }
+
+    /// Ranks deciding ties within the category, most significant first.
+    ///
+    /// e.g. `[Five, Eight]` for fives full of eights,
+    /// `[Five, Four, Three, Two, Ace]` for the wheel.
+    pub fn ranks(&self) -> &[Rank] {
+        &self.ranks
+    }
+}
+
+impl HandValue {
+    /// Plain English description, e.g. `"Full house, fives over eights"` or `"Ace-high flush"`.
+    pub fn describe(&self) -> String {
+        let r = &self.ranks;
+        let text = match self.category {
+            Hands::FiveOfAKind => format!("five of a kind, {}", r[0].plural()),
+            Hands::StraightFlush if r[0] == Rank::Ace => "royal flush".to_string(),
+            Hands::StraightFlush => format!("{}-high straight flush", r[0].name()),
+            Hands::FourOfAKind => format!("four of a kind, {}", r[0].plural()),
+            Hands::FullHouse => format!("full house, {} over {}", r[0].plural(), r[1].plural()),
+            Hands::Flush => format!("{}-high flush", r[0].name()),
+            Hands::Straight => format!("{}-high straight", r[0].name()),
+            Hands::ThreeOfAKind => format!("three of a kind, {}", r[0].plural()),
+            Hands::TwoPair => format!("two pair, {} and {}", r[0].plural(), r[1].plural()),
+            Hands::OnePair => format!("pair of {}", r[0].plural()),
+            Hands::HighCard => format!("{} high", r[0].name()),
+        };
+        capitalize(&text)
+    }
+
+    /// How many of the leading ranks [`HandValue::describe`] mentions.
+    fn described_ranks(&self) -> usize {
+        match self.category {
+            Hands::FullHouse | Hands::TwoPair => 2,
+            _ => 1,
+        }
+    }
+
+    /// What the rank at `index` of [`HandValue::ranks`] stands for, e.g. `"kicker"`.
+    fn component(&self, index: usize) -> &'static str {
+        const CARDS: [&str; 5] = [
+            "highest card",
+            "second card",
+            "third card",
+            "fourth card",
+            "fifth card",
+        ];
+        const KICKERS: [&str; 4] = ["kicker", "second kicker", "third kicker", "fourth kicker"];
+        match (self.category, index) {
+            (Hands::Flush | Hands::HighCard, i) => CARDS[i],
+            (Hands::StraightFlush | Hands::Straight, _) => "high card",
+            (Hands::FullHouse, 0) => "three of a kind",
+            (Hands::FullHouse, _) => "pair",
+            (Hands::TwoPair, 0) => "top pair",
+            (Hands::TwoPair, 1) => "second pair",
+            (Hands::FiveOfAKind | Hands::FourOfAKind | Hands::ThreeOfAKind, 0) => "rank",
+            (Hands::OnePair, 0) => "pair",
+            (Hands::TwoPair, i) => KICKERS[i - 2],
+            (_, i) => KICKERS[i - 1],
+        }
+    }
+
+    /// Compare with `other`, explaining what decided the outcome.
+    pub fn compare_explain(&self, other: &Self) -> (Ordering, String) {
+        let ordering = self.cmp(other);
+        if self.category != other.category {
+            let (winner, loser) = match ordering {
+                Ordering::Less => (other, self),
+                _ => (self, other),
+            };
+            let text = format!(
+                "{} beats {}",
+                winner.describe(),
+                lowercase(&loser.describe())
+            );
+            return (ordering, text);
+        }
+        // partial hands can have fewer ranks; the first rank only one side has decides
+        let len = self.ranks.len().min(other.ranks.len());
+        let i = (0..len)
+            .find(|&i| self.ranks[i] != other.ranks[i])
+            .unwrap_or(len);
+        if ordering == Ordering::Equal {
+            return (
+                ordering,
+                format!("both have {}; split pot", lowercase(&self.describe())),
+            );
+        }
+        let longer = if i < self.ranks.len() { self } else { other };
+        let shared = if i < longer.described_ranks() {
+            self.category.to_string()
+        } else {
+            lowercase(&self.describe())
+        };
+        let rank = |a: &Self| a.ranks.get(i).map_or("none".to_string(), Rank::to_string);
+        let text = format!(
+            "both have {shared}; decided by {} {} vs {}",
+            longer.component(i),
+            rank(self),
+            rank(other)
+        );
+        (ordering, text)
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(a) => a.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn lowercase(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(a) => a.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,422 @@
+//! Hand histories as written by online poker sites.
+//!
+//! A [`HandHistory`] is parsed from the text of one hand, see [`pokerstars`] for the format,
+//! and can check its reported winners against the crate's evaluator with [`HandHistory::verify`].
+//! It is written back as text by its `Display` impl and as Open Hand History JSON by
+//! [`HandHistory::to_ohh`]; [`HandHistory::from_game`] records a hand played by the
+//! [`holdem::game`](crate::holdem::game) engine.
+//!
+//! Amounts are integers in hundredths of the currency or chip, so `$0.25` is `25` and
+//! a tournament stack of `1500` chips is `150000`.
+
+mod ohh;
+pub mod pokerstars;
+
+pub use crate::holdem::game::Street;
+
+use crate::{
+    holdem::{best_of, game},
+    omaha,
+    pot::{self, OddChip},
+    Card, HandValue, HistoryError,
+};
+
+/// The site a hand history came from, which decides the header line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Site {
+    /// `PokerStars Hand #...`
+    PokerStars,
+    /// `Poker Hand #...`, GGPoker's take on the same format.
+    GgPoker,
+}
+
+/// A player in the hand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seat {
+    /// Seat number as written, counting from 1.
+    pub seat: u8,
+    pub name: String,
+    /// Chips at the start of the hand.
+    pub stack: u64,
+    /// Hole cards, when dealt to the hero or shown.
+    pub hole: Option<Vec<Card>>,
+    /// Whether the player showed their cards at showdown.
+    pub shown: bool,
+    /// Everything the player collected from the pots.
+    pub won: u64,
+    pub sitting_out: bool,
+}
+
+/// What a player did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    SmallBlind(u64),
+    BigBlind(u64),
+    /// A small and a big blind posted together, e.g. on coming back to the table.
+    /// The amount is both of them; only the big blind counts towards the bet.
+    SmallAndBigBlind(u64),
+    Ante(u64),
+    Fold,
+    Check,
+    Call(u64),
+    Bet(u64),
+    /// Raise by `by`, bringing the player's total for the street to `to`.
+    Raise {
+        by: u64,
+        to: u64,
+    },
+}
+
+impl Action {
+    /// Whether this is a blind or an ante, posted before the cards are dealt.
+    fn is_post(&self) -> bool {
+        matches!(
+            self,
+            Action::SmallBlind(_)
+                | Action::BigBlind(_)
+                | Action::SmallAndBigBlind(_)
+                | Action::Ante(_)
+        )
+    }
+}
+
+/// One action, in the order it happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Play {
+    pub street: Street,
+    pub player: String,
+    pub action: Action,
+    pub all_in: bool,
+}
+
+/// One complete hand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandHistory {
+    pub site: Site,
+    pub id: String,
+    /// The game as written in the header, e.g. `Hold'em No Limit`.
+    pub game: String,
+    pub small_blind: u64,
+    pub big_blind: u64,
+    /// Symbol written before amounts, e.g. `$`; `None` for play money and tournament chips.
+    pub symbol: Option<char>,
+    /// Currency code written after the stakes, e.g. `USD`.
+    pub currency: Option<String>,
+    /// Date and time as written in the header.
+    pub date: String,
+    pub table: String,
+    pub max_seats: u8,
+    /// Seat number of the button.
+    pub button: u8,
+    /// Players in seat order.
+    pub seats: Vec<Seat>,
+    /// The player whose hole cards were dealt face up to the writer of the history.
+    pub hero: Option<String>,
+    pub plays: Vec<Play>,
+    pub board: Vec<Card>,
+    /// A bet nobody called, returned to the player who made it.
+    pub uncalled: Option<(String, u64)>,
+    pub total_pot: u64,
+    pub rake: u64,
+}
+
+impl HandHistory {
+    /// The history of a hand played with the [`holdem::game`](crate::holdem::game) engine,
+    /// `names` naming the players in seat order.
+    ///
+    /// Chips are written as whole amounts without a currency. Hole cards are kept for the
+    /// players who went to showdown, the winners being those the evaluator picked. The hand is
+    /// `PokerStars Hand #1` at `Table 1`, dated `1970/01/01 00:00:00 UTC`; change the fields
+    /// before writing it to say otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `names` does not name every player.
+    ///
+    /// ```
+    /// use poker::{deck::Deck, history::HandHistory, holdem::game::{Action, Stakes, State}};
+    ///
+    /// let deck: Deck = "AH KS AD KD 2C 7H TS JD 3C 4C 5C 6C".parse().unwrap();
+    /// let state = State::new(Stakes::new(1, 2), &[100, 100], 0, deck).unwrap();
+    /// let state = state.apply(Action::Raise(6)).unwrap().apply(Action::Fold).unwrap();
+    /// let hand = HandHistory::from_game(&state, &["Alice", "Bob"]);
+    /// assert_eq!(hand.uncalled, Some(("Alice".to_string(), 400)));
+    /// assert_eq!(hand.to_string().parse::<HandHistory>().unwrap(), hand);
+    /// ```
+    pub fn from_game(state: &game::State, names: &[&str]) -> Self {
+        let players = state.players();
+        let n = players.len();
+        assert!(names.len() >= n, "a name for each of {n} players");
+        let stakes = state.stakes();
+        let payouts = state
+            .outcome()
+            .map_or_else(|| vec![0; n], |a| a.payouts.clone());
+        let mut stacks = (players.iter().zip(&payouts))
+            .map(|(a, payout)| a.stack + a.contributed - payout)
+            .collect::<Vec<_>>();
+        let seats = (0..n)
+            .map(|i| Seat {
+                seat: i as u8 + 1,
+                name: names[i].to_string(),
+                stack: stacks[i] * 100,
+                hole: None,
+                shown: false,
+                won: 0,
+                sitting_out: false,
+            })
+            .collect::<Vec<_>>();
+
+        // replay the betting to recover the amounts the engine's actions leave implicit
+        let mut plays = vec![];
+        let mut street_bet = vec![0; n];
+        let (small, big) = match n {
+            2 => (state.button(), (state.button() + 1) % n),
+            _ => ((state.button() + 1) % n, (state.button() + 2) % n),
+        };
+        for (seat, blind, action) in [
+            (
+                small,
+                stakes.small_blind,
+                Action::SmallBlind as fn(u64) -> Action,
+            ),
+            (big, stakes.big_blind, Action::BigBlind),
+        ] {
+            let posted = blind.min(stacks[seat]);
+            stacks[seat] -= posted;
+            street_bet[seat] = posted;
+            plays.push(Play {
+                street: Street::Preflop,
+                player: names[seat].to_string(),
+                action: action(posted * 100),
+                all_in: stacks[seat] == 0,
+            });
+        }
+        let mut street = Street::Preflop;
+        let mut current = stakes.big_blind;
+        for &(on, seat, action) in state.actions() {
+            if on != street {
+                street = on;
+                street_bet.iter_mut().for_each(|a| *a = 0);
+                current = 0;
+            }
+            let added = match action {
+                game::Action::Fold | game::Action::Check => 0,
+                game::Action::Call => current.saturating_sub(street_bet[seat]).min(stacks[seat]),
+                game::Action::Bet(to) | game::Action::Raise(to) => to - street_bet[seat],
+            };
+            let action = match action {
+                game::Action::Fold => Action::Fold,
+                game::Action::Check => Action::Check,
+                game::Action::Call => Action::Call(added * 100),
+                game::Action::Bet(to) => Action::Bet(to * 100),
+                game::Action::Raise(to) => Action::Raise {
+                    by: (to - current) * 100,
+                    to: to * 100,
+                },
+            };
+            stacks[seat] -= added;
+            street_bet[seat] += added;
+            current = current.max(street_bet[seat]);
+            plays.push(Play {
+                street,
+                player: names[seat].to_string(),
+                action,
+                all_in: added > 0 && stacks[seat] == 0,
+            });
+        }
+
+        // chips above everyone else's were never called and go back to whoever put them in
+        let contributions = players.iter().map(|a| a.contributed).collect::<Vec<_>>();
+        let top = (0..n).max_by_key(|&i| contributions[i]).unwrap_or_default();
+        let second = (0..n)
+            .filter(|&i| i != top)
+            .map(|i| contributions[i])
+            .max()
+            .unwrap_or_default();
+        let uncalled = contributions[top].saturating_sub(second);
+        let mut history = HandHistory {
+            site: Site::PokerStars,
+            id: "1".to_string(),
+            game: "Hold'em No Limit".to_string(),
+            small_blind: stakes.small_blind * 100,
+            big_blind: stakes.big_blind * 100,
+            symbol: None,
+            currency: None,
+            date: "1970/01/01 00:00:00 UTC".to_string(),
+            table: "Table 1".to_string(),
+            max_seats: n as u8,
+            button: state.button() as u8 + 1,
+            seats,
+            hero: None,
+            plays,
+            board: state.board().to_vec(),
+            uncalled: (uncalled > 0).then(|| (names[top].to_string(), uncalled * 100)),
+            total_pot: (contributions.iter().sum::<u64>() - uncalled) * 100,
+            rake: 0,
+        };
+        for (i, seat) in history.seats.iter_mut().enumerate() {
+            let returned = if i == top { uncalled } else { 0 };
+            seat.won = payouts[i].saturating_sub(returned) * 100;
+            if let Some(outcome) = state.outcome() {
+                if outcome.shown[i].is_some() {
+                    seat.hole = Some(players[i].hole.to_vec());
+                    seat.shown = true;
+                }
+            }
+        }
+        history
+    }
+
+    /// Index in [`HandHistory::seats`] of the player called `name`.
+    pub fn seat_of(&self, name: &str) -> Option<usize> {
+        self.seats.iter().position(|a| a.name == name)
+    }
+
+    /// Chips each seat put into the pot, after any uncalled bet is returned.
+    ///
+    /// Fails with [`HistoryError::BadAmount`] when a player takes back more than they put in,
+    /// raises to less than they already bet on the street, or posts blinds adding up to less
+    /// than the big blind.
+    pub fn contributions(&self) -> Result<Vec<u64>, HistoryError> {
+        let mut total = vec![0u64; self.seats.len()];
+        let mut street = vec![0u64; self.seats.len()];
+        let mut current = Street::Preflop;
+        let bad = |i: usize| HistoryError::BadAmount {
+            player: self.seats[i].name.clone(),
+        };
+        for play in &self.plays {
+            if play.street != current {
+                current = play.street;
+                street.iter_mut().for_each(|a| *a = 0);
+            }
+            let Some(i) = self.seat_of(&play.player) else {
+                continue;
+            };
+            let before = street[i];
+            let (added, posted) = match play.action {
+                Action::SmallBlind(a) | Action::BigBlind(a) | Action::Call(a) | Action::Bet(a) => {
+                    (a, 0)
+                }
+                Action::SmallAndBigBlind(a) => (
+                    self.big_blind,
+                    a.checked_sub(self.big_blind).ok_or_else(|| bad(i))?,
+                ),
+                Action::Raise { to, .. } => (to.checked_sub(before).ok_or_else(|| bad(i))?, 0),
+                Action::Ante(a) => (0, a),
+                Action::Fold | Action::Check => (0, 0),
+            };
+            street[i] = before.checked_add(added).ok_or_else(|| bad(i))?;
+            total[i] = (total[i].checked_add(added))
+                .and_then(|a| a.checked_add(posted))
+                .ok_or_else(|| bad(i))?;
+        }
+        if let Some((name, amount)) = &self.uncalled {
+            if let Some(i) = self.seat_of(name) {
+                total[i] = total[i].checked_sub(*amount).ok_or_else(|| bad(i))?;
+            }
+        }
+        Ok(total)
+    }
+
+    /// Check the reported winners against the evaluator.
+    ///
+    /// The pots are rebuilt from the actions and awarded to the best hands shown down,
+    /// players who did not show counting as beaten; every player who collected chips must
+    /// win some of them and nobody else may. Hi/lo games, whose pots are split another way,
+    /// are not checked.
+    pub fn verify(&self) -> Result<(), HistoryError> {
+        if self.game.contains("Hi/Lo") {
+            return Ok(());
+        }
+        let folded = (self.seats.iter())
+            .map(|a| {
+                self.plays
+                    .iter()
+                    .any(|b| b.player == a.name && b.action == Action::Fold)
+            })
+            .collect::<Vec<_>>();
+        let values = (self.seats.iter())
+            .zip(&folded)
+            .map(
+                |(seat, &folded)| match (&seat.hole, folded || !seat.shown) {
+                    (Some(hole), false) => self.value(hole),
+                    _ => None,
+                },
+            )
+            .collect::<Vec<_>>();
+        let button = (self.seats.iter())
+            .rposition(|a| a.seat <= self.button)
+            .unwrap_or(self.seats.len() - 1);
+        let payouts = pot::distribute(
+            &self.contributions()?,
+            &folded,
+            &values,
+            OddChip::LeftOfButton(button),
+        )
+        .map_err(|_| HistoryError::EveryoneFolded)?;
+        let names = |won: &dyn Fn(usize) -> bool| {
+            (0..self.seats.len())
+                .filter(|&i| won(i))
+                .map(|i| self.seats[i].name.clone())
+                .collect::<Vec<_>>()
+        };
+        let reported = names(&|i| self.seats[i].won > 0);
+        let expected = names(&|i| payouts[i] > 0);
+        if reported == expected {
+            Ok(())
+        } else {
+            Err(HistoryError::WrongWinners { reported, expected })
+        }
+    }
+
+    /// Value of the best hand `hole` makes with the board, four or more hole cards meaning Omaha.
+    fn value(&self, hole: &[Card]) -> Option<HandValue> {
+        match hole.len() {
+            2 if self.board.len() >= 3 => Some(best_of(&[hole, &self.board].concat()).evaluate()),
+            4..=6 => omaha::best_hand(hole, &self.board)
+                .ok()
+                .map(|a| a.evaluate()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::holdem::game::{Stakes, State};
+
+    #[test]
+    fn engine_hand_reads_back() {
+        // seat 2 calls all-in with kings against aces, the rest of the raise is uncalled
+        let deck = "AH KS 7C AD KD 2D 3C QH 8S 4D 5C 9H 6C JS".parse().unwrap();
+        let state = State::new(Stakes::new(1, 2), &[100, 100, 40], 0, deck).unwrap();
+        let state = [
+            game::Action::Fold,
+            game::Action::Raise(60),
+            game::Action::Call,
+        ]
+        .into_iter()
+        .fold(state, |state, action| state.apply(action).unwrap());
+        assert!(state.is_complete());
+
+        let hand = HandHistory::from_game(&state, &["Ann", "Ben", "Cat"]);
+        assert_eq!(hand.contributions(), Ok(vec![0, 4000, 4000]));
+        assert_eq!(hand.uncalled, Some(("Ben".to_string(), 2000)));
+        assert_eq!(hand.plays[3].action, Action::Raise { by: 5800, to: 6000 });
+        assert_eq!(
+            hand.plays[4],
+            Play {
+                street: Street::Preflop,
+                player: "Cat".to_string(),
+                action: Action::Call(3800),
+                all_in: true,
+            }
+        );
+        assert_eq!(hand.board.len(), 5);
+        assert!(hand.seats[1].shown && hand.seats[2].shown);
+        assert_eq!(hand.seats[1].won, 8000);
+        assert_eq!(hand.verify(), Ok(()));
+        assert_eq!(hand.to_string().parse::<HandHistory>(), Ok(hand));
+    }
+}
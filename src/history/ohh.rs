@@ -0,0 +1,298 @@
+//! The Open Hand History JSON standard, <https://hh-specs.handhistory.org>.
+
+use super::{Action, HandHistory, Site, Street};
+use crate::{card::Notation, json::string};
+use std::fmt::Write;
+
+impl HandHistory {
+    /// The hand as an Open Hand History object, `{"ohh": {...}}`, on one line.
+    ///
+    /// Players are identified by their seat number. The header's date is written as
+    /// `start_date_utc` without converting its time zone, and the pot is written as a single
+    /// pot paid to everyone who collected from it, since the history does not say how it split.
+    /// A raise's amount is the player's total for the street, like the text's `raises ... to`.
+    ///
+    /// ```
+    /// use poker::history::HandHistory;
+    ///
+    /// let text = "\
+    /// PokerStars Hand #1:  Hold'em No Limit ($0.01/$0.02 USD) - 2021/12/27 20:14:59 ET
+    /// Table 'Aaltje III' 2-max Seat #1 is the button
+    /// Seat 1: Alice ($2 in chips)
+    /// Seat 2: Bob ($2 in chips)
+    /// Alice: posts small blind $0.01
+    /// Bob: posts big blind $0.02
+    /// *** HOLE CARDS ***
+    /// Alice: folds
+    /// Uncalled bet ($0.01) returned to Bob
+    /// Bob collected $0.02 from pot
+    /// *** SUMMARY ***
+    /// Total pot $0.02 | Rake $0
+    /// ";
+    /// let hand: HandHistory = text.parse().unwrap();
+    /// let json = hand.to_ohh();
+    /// assert!(json.starts_with(r#"{"ohh":{"spec_version":"1.4.6","site_name":"PokerStars""#));
+    /// assert!(json.contains(r#""action":"Post SB","amount":0.01"#));
+    /// ```
+    pub fn to_ohh(&self) -> String {
+        let site = match self.site {
+            Site::PokerStars => "PokerStars",
+            Site::GgPoker => "GGPoker",
+        };
+        let game_type = match (self.game.contains("Omaha"), self.game.contains("Hi/Lo")) {
+            (true, true) => "OmahaHiLo",
+            (true, false) => "Omaha",
+            (false, _) => "Holdem",
+        };
+        let bet_type = if self.game.contains("No Limit") {
+            "NL"
+        } else if self.game.contains("Pot Limit") {
+            "PL"
+        } else {
+            "FL"
+        };
+        let id = |name: &str| self.seat_of(name).map_or(0, |i| self.seats[i].seat);
+
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            r#"{{"ohh":{{"spec_version":"1.4.6","site_name":{},"network_name":{},"internal_version":{},"game_number":{},"start_date_utc":{},"table_name":{},"table_size":{},"game_type":"{game_type}","bet_limit":{{"bet_type":"{bet_type}","bet_cap":0}},"dealer_seat":{},"small_blind_amount":{},"big_blind_amount":{},"ante_amount":{}"#,
+            string(site),
+            string(site),
+            string(env!("CARGO_PKG_VERSION")),
+            string(&self.id),
+            string(&date(&self.date)),
+            string(&self.table),
+            self.max_seats,
+            self.button,
+            number(self.small_blind),
+            number(self.big_blind),
+            number(
+                (self.plays.iter())
+                    .find_map(|a| match a.action {
+                        Action::Ante(a) => Some(a),
+                        _ => None,
+                    })
+                    .unwrap_or_default()
+            ),
+        );
+        if let Some(hero) = &self.hero {
+            let _ = write!(out, r#","hero_player_id":{}"#, id(hero));
+        }
+        let currency = self.currency.as_deref().or(match self.symbol {
+            Some('$') => Some("USD"),
+            Some('€') => Some("EUR"),
+            Some('£') => Some("GBP"),
+            _ => None,
+        });
+        if let Some(currency) = currency {
+            let _ = write!(out, r#","currency":{}"#, string(currency));
+        }
+
+        let players = (self.seats.iter())
+            .map(|a| {
+                format!(
+                    r#"{{"id":{},"seat":{},"name":{},"display":{},"starting_stack":{}{}}}"#,
+                    a.seat,
+                    a.seat,
+                    string(&a.name),
+                    string(&a.name),
+                    number(a.stack),
+                    if a.sitting_out {
+                        r#","is_sitting_out":true"#
+                    } else {
+                        ""
+                    }
+                )
+            })
+            .collect::<Vec<_>>();
+        let _ = write!(out, r#","players":[{}]"#, players.join(","));
+
+        let mut number_of_action = 0;
+        let mut action = |player: u8, action: &str, amount: Option<u64>, rest: &str| {
+            number_of_action += 1;
+            let amount = amount.map(|a| format!(r#","amount":{}"#, number(a)));
+            format!(
+                r#"{{"action_number":{number_of_action},"player_id":{player},"action":"{action}"{}{rest}}}"#,
+                amount.unwrap_or_default()
+            )
+        };
+        let mut rounds = vec![];
+        for (round, street) in [Street::Preflop, Street::Flop, Street::Turn, Street::River]
+            .into_iter()
+            .enumerate()
+        {
+            let dealt = match street {
+                Street::Preflop => Some(&self.board[..0]),
+                Street::Flop => self.board.get(..3),
+                Street::Turn => self.board.get(3..4),
+                Street::River => self.board.get(4..5),
+            };
+            let Some(dealt) = dealt else { break };
+            let mut actions = vec![];
+            // the hero's cards are dealt once the blinds are in
+            let hero = (self.hero.as_deref())
+                .and_then(|a| self.seats.get(self.seat_of(a)?))
+                .filter(|_| street == Street::Preflop);
+            let mut deal = hero.and_then(|a| Some((a.seat, a.hole.as_deref()?)));
+            for play in self.plays.iter().filter(|a| a.street == street) {
+                if !play.action.is_post() {
+                    if let Some((seat, hole)) = deal.take() {
+                        let cards = format!(r#","cards":{}"#, cards(hole));
+                        actions.push(action(seat, "Dealt Cards", None, &cards));
+                    }
+                }
+                let player = id(&play.player);
+                let all_in = format!(r#","is_allin":{}"#, play.all_in);
+                let (name, amount) = match play.action {
+                    Action::SmallBlind(a) => ("Post SB", Some(a)),
+                    Action::BigBlind(a) => ("Post BB", Some(a)),
+                    Action::SmallAndBigBlind(a) => {
+                        let big = a.min(self.big_blind);
+                        actions.push(action(player, "Post BB", Some(big), &all_in));
+                        ("Post Dead", Some(a - big))
+                    }
+                    Action::Ante(a) => ("Post Ante", Some(a)),
+                    Action::Fold => ("Fold", None),
+                    Action::Check => ("Check", None),
+                    Action::Call(a) => ("Call", Some(a)),
+                    Action::Bet(a) => ("Bet", Some(a)),
+                    Action::Raise { to, .. } => ("Raise", Some(to)),
+                };
+                actions.push(action(player, name, amount, &all_in));
+            }
+            if let Some((seat, hole)) = deal {
+                let cards = format!(r#","cards":{}"#, cards(hole));
+                actions.push(action(seat, "Dealt Cards", None, &cards));
+            }
+            rounds.push(format!(
+                r#"{{"id":{round},"street":"{street:?}","cards":{},"actions":[{}]}}"#,
+                cards(dealt),
+                actions.join(",")
+            ));
+        }
+        if self.seats.iter().any(|a| a.shown) {
+            let actions = (self.seats.iter().filter(|a| a.shown))
+                .filter_map(|a| {
+                    let cards = format!(r#","cards":{}"#, cards(a.hole.as_deref()?));
+                    Some(action(a.seat, "Shows Cards", None, &cards))
+                })
+                .collect::<Vec<_>>();
+            rounds.push(format!(
+                r#"{{"id":{},"street":"Showdown","actions":[{}]}}"#,
+                rounds.len(),
+                actions.join(",")
+            ));
+        }
+        let _ = write!(out, r#","rounds":[{}]"#, rounds.join(","));
+
+        let wins = (self.seats.iter().filter(|a| a.won > 0))
+            .map(|a| {
+                format!(
+                    r#"{{"player_id":{},"win_amount":{},"contributed_rake":0}}"#,
+                    a.seat,
+                    number(a.won)
+                )
+            })
+            .collect::<Vec<_>>();
+        let _ = write!(
+            out,
+            r#","pots":[{{"number":0,"amount":{},"rake":{},"player_wins":[{}]}}]}}}}"#,
+            number(self.total_pot),
+            number(self.rake),
+            wins.join(",")
+        );
+        out
+    }
+}
+
+/// `2021/12/27 20:14:59 ET` as `2021-12-27T20:14:59`.
+fn date(value: &str) -> String {
+    let mut parts = value.split_whitespace();
+    let day = parts.next().unwrap_or_default().replace('/', "-");
+    match parts.next() {
+        Some(time) => format!("{day}T{time}"),
+        None => day,
+    }
+}
+
+/// An amount in hundredths as a JSON number.
+fn number(value: u64) -> String {
+    match value % 100 {
+        0 => (value / 100).to_string(),
+        cents => format!("{}.{cents:02}", value / 100),
+    }
+}
+
+fn cards(cards: &[crate::Card]) -> String {
+    let cards = cards
+        .iter()
+        .map(|a| string(&a.display(Notation::Short).to_string()))
+        .collect::<Vec<_>>();
+    format!("[{}]", cards.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    const HAND: &str = "\
+PokerStars Hand #42:  Hold'em No Limit ($0.01/$0.02 USD) - 2021/12/27 20:14:59 ET
+Table 'Say \"hi\"' 3-max Seat #1 is the button
+Seat 1: Alice ($2 in chips)
+Seat 2: Bob ($0.50 in chips)
+Seat 3: Carol ($2 in chips)
+Bob: posts small blind $0.01
+Carol: posts big blind $0.02
+*** HOLE CARDS ***
+Dealt to Alice [Ah Kd]
+Alice: raises $0.08 to $0.10
+Bob: raises $0.40 to $0.50 and is all-in
+Carol: folds
+Alice: calls $0.40
+*** FLOP *** [2c 7h Ts]
+*** TURN *** [2c 7h Ts] [Jd]
+*** RIVER *** [2c 7h Ts Jd] [Kc]
+*** SHOW DOWN ***
+Bob: shows [Qh Qd]
+Alice: shows [Ah Kd]
+Alice collected $1.02 from pot
+*** SUMMARY ***
+Total pot $1.02 | Rake $0
+";
+
+    #[test]
+    fn valid_json() {
+        let hand: super::HandHistory = HAND.parse().unwrap();
+        let json: Value = serde_json::from_str(&hand.to_ohh()).unwrap();
+        let ohh = &json["ohh"];
+        assert_eq!(ohh["game_number"], "42");
+        assert_eq!(ohh["table_name"], "Say \"hi\"");
+        assert_eq!(ohh["start_date_utc"], "2021-12-27T20:14:59");
+        assert_eq!(ohh["game_type"], "Holdem");
+        assert_eq!(ohh["bet_limit"]["bet_type"], "NL");
+        assert_eq!(ohh["big_blind_amount"], 0.02);
+        assert_eq!(ohh["hero_player_id"], 1);
+        assert_eq!(ohh["currency"], "USD");
+        assert_eq!(ohh["players"][1]["starting_stack"], 0.5);
+
+        let rounds = ohh["rounds"].as_array().unwrap();
+        let streets = rounds.iter().map(|a| &a["street"]).collect::<Vec<_>>();
+        assert_eq!(streets, ["Preflop", "Flop", "Turn", "River", "Showdown"]);
+        let preflop = rounds[0]["actions"].as_array().unwrap();
+        assert_eq!(preflop[0]["action"], "Post SB");
+        assert_eq!(preflop[2]["action"], "Dealt Cards");
+        assert_eq!(preflop[2]["cards"][0], "Ah");
+        assert_eq!(preflop[4]["action"], "Raise");
+        assert_eq!(preflop[4]["amount"], 0.5);
+        assert_eq!(preflop[4]["is_allin"], true);
+        assert_eq!(preflop[6]["action_number"], 7);
+        assert_eq!(rounds[3]["cards"][0], "Kc");
+        assert_eq!(rounds[4]["actions"][0]["action"], "Shows Cards");
+        assert_eq!(rounds[4]["actions"][0]["action_number"], 8);
+
+        let pot = &ohh["pots"][0];
+        assert_eq!(pot["amount"], 1.02);
+        assert_eq!(pot["player_wins"][0]["player_id"], 1);
+    }
+}
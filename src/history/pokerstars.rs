@@ -0,0 +1,750 @@
+//! The PokerStars text format, which GGPoker also writes with its own header.
+//!
+//! ```text
+//! PokerStars Hand #233059016815:  Hold'em No Limit ($0.01/$0.02 USD) - 2021/12/27 20:14:59 ET
+//! Table 'Aaltje III' 6-max Seat #3 is the button
+//! Seat 1: Alice ($2.05 in chips)
+//! Seat 3: Bob ($2 in chips)
+//! Alice: posts small blind $0.01
+//! Bob: posts big blind $0.02
+//! *** HOLE CARDS ***
+//! Dealt to Bob [Ah Kd]
+//! Alice: folds
+//! Uncalled bet ($0.01) returned to Bob
+//! Bob collected $0.02 from pot
+//! *** SUMMARY ***
+//! Total pot $0.02 | Rake $0
+//! ```
+//!
+//! Lines the hand does not depend on, such as chat or players joining the table, are skipped,
+//! but an action line that cannot be read is an error.
+//!
+//! A [`HandHistory`] is written back in the same format by its [`Display`](fmt::Display) impl.
+
+use super::{Action, HandHistory, Play, Seat, Site, Street};
+use crate::{
+    card::{parse_cards, Notated, Notation},
+    Card, HistoryError,
+};
+use std::{fmt, str::FromStr};
+
+impl FromStr for HandHistory {
+    type Err = HistoryError;
+
+    /// The text of exactly one hand. Reported winners are not checked, see [`HandHistory::verify`],
+    /// but amounts that do not add up, such as an uncalled bet larger than the bet, are an error.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut lines = (value.lines().enumerate())
+            .map(|(i, a)| (i + 1, a.trim_end()))
+            .filter(|a| !a.1.is_empty());
+        let header = lines
+            .next()
+            .ok_or(HistoryError::MissingLine { expected: "header" })?;
+        let mut history = header_line(header.0, header.1.trim_start_matches('\u{feff}'))?;
+        let table = lines
+            .next()
+            .ok_or(HistoryError::MissingLine { expected: "table" })?;
+        table_line(&mut history, table.0, table.1)?;
+
+        let mut summary = false;
+        let mut street = Street::Preflop;
+        for (n, line) in lines {
+            if let Some(marker) = line.strip_prefix("*** ") {
+                let (name, rest) = marker.split_once(" ***").ok_or_else(|| bad(n, line))?;
+                let cards = brackets(n, rest)?;
+                match (name, cards.last()) {
+                    ("HOLE CARDS" | "SHOW DOWN" | "SHOWDOWN", _) => {}
+                    ("SUMMARY", _) => summary = true,
+                    ("FLOP", Some(cards)) if cards.len() == 3 => {
+                        street = Street::Flop;
+                        history.board = cards.clone();
+                    }
+                    ("TURN", Some(cards)) | ("RIVER", Some(cards)) if cards.len() == 1 => {
+                        street = if name == "TURN" {
+                            Street::Turn
+                        } else {
+                            Street::River
+                        };
+                        history.board.extend(cards);
+                    }
+                    _ => return Err(bad(n, line)),
+                }
+            } else if summary {
+                summary_line(&mut history, n, line)?;
+            } else if line.starts_with("Seat ") && history.plays.is_empty() {
+                history.seats.push(seat_line(n, line)?);
+            } else {
+                body_line(&mut history, street, n, line)?;
+            }
+        }
+        history.contributions()?;
+        Ok(history)
+    }
+}
+
+impl fmt::Display for HandHistory {
+    /// The hand as PokerStars writes it, or GGPoker for [`Site::GgPoker`], ending in a newline.
+    ///
+    /// Only what the history holds is written: the summary lists the pot and board but not
+    /// each seat's result, and shown hands are described by the crate's evaluator.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let money = |value| Money(value, self.symbol);
+        match self.site {
+            Site::PokerStars => write!(f, "PokerStars Hand #{}:  ", self.id)?,
+            Site::GgPoker => write!(f, "Poker Hand #{}: ", self.id)?,
+        }
+        write!(
+            f,
+            "{} ({}/{}",
+            self.game,
+            money(self.small_blind),
+            money(self.big_blind)
+        )?;
+        if let Some(currency) = &self.currency {
+            write!(f, " {currency}")?;
+        }
+        writeln!(f, ") - {}", self.date)?;
+        writeln!(
+            f,
+            "Table '{}' {}-max Seat #{} is the button",
+            self.table, self.max_seats, self.button
+        )?;
+        for seat in &self.seats {
+            write!(
+                f,
+                "Seat {}: {} ({} in chips)",
+                seat.seat,
+                seat.name,
+                money(seat.stack)
+            )?;
+            if seat.sitting_out {
+                write!(f, " is sitting out")?;
+            }
+            writeln!(f)?;
+        }
+
+        let posts = (self.plays.iter())
+            .position(|a| !a.action.is_post())
+            .unwrap_or(self.plays.len());
+        for play in &self.plays[..posts] {
+            self.write_play(f, play)?;
+        }
+        writeln!(f, "*** HOLE CARDS ***")?;
+        let hero = (self.hero.as_deref()).and_then(|a| self.seats.get(self.seat_of(a)?));
+        if let Some(Seat {
+            name,
+            hole: Some(hole),
+            ..
+        }) = hero
+        {
+            writeln!(f, "Dealt to {name} [{}]", cards(hole))?;
+        }
+        let mut street = Street::Preflop;
+        for play in &self.plays[posts..] {
+            while street < play.street {
+                street = street.next();
+                self.write_street(f, street)?;
+            }
+            self.write_play(f, play)?;
+        }
+        // the board still runs out after everyone is all-in
+        while street < Street::River && self.board.len() > board_size(street) {
+            street = street.next();
+            self.write_street(f, street)?;
+        }
+        if let Some((name, value)) = &self.uncalled {
+            writeln!(f, "Uncalled bet ({}) returned to {name}", money(*value))?;
+        }
+        if self.seats.iter().any(|a| a.shown) {
+            writeln!(f, "*** SHOW DOWN ***")?;
+        }
+        for seat in self.seats.iter().filter(|a| a.shown) {
+            let Some(hole) = &seat.hole else { continue };
+            write!(f, "{}: shows [{}]", seat.name, cards(hole))?;
+            if let Some(value) = self.value(hole) {
+                write!(f, " ({})", value.describe())?;
+            }
+            writeln!(f)?;
+        }
+        for seat in self.seats.iter().filter(|a| a.won > 0) {
+            writeln!(f, "{} collected {} from pot", seat.name, money(seat.won))?;
+        }
+        writeln!(f, "*** SUMMARY ***")?;
+        writeln!(
+            f,
+            "Total pot {} | Rake {}",
+            money(self.total_pot),
+            money(self.rake)
+        )?;
+        if !self.board.is_empty() {
+            writeln!(f, "Board [{}]", cards(&self.board))?;
+        }
+        Ok(())
+    }
+}
+
+impl HandHistory {
+    fn write_play(&self, f: &mut fmt::Formatter<'_>, play: &Play) -> fmt::Result {
+        let money = |value| Money(value, self.symbol);
+        write!(f, "{}: ", play.player)?;
+        match play.action {
+            Action::SmallBlind(a) => write!(f, "posts small blind {}", money(a))?,
+            Action::BigBlind(a) => write!(f, "posts big blind {}", money(a))?,
+            Action::SmallAndBigBlind(a) => write!(f, "posts small & big blinds {}", money(a))?,
+            Action::Ante(a) => write!(f, "posts the ante {}", money(a))?,
+            Action::Fold => write!(f, "folds")?,
+            Action::Check => write!(f, "checks")?,
+            Action::Call(a) => write!(f, "calls {}", money(a))?,
+            Action::Bet(a) => write!(f, "bets {}", money(a))?,
+            Action::Raise { by, to } => write!(f, "raises {} to {}", money(by), money(to))?,
+        }
+        if play.all_in {
+            write!(f, " and is all-in")?;
+        }
+        writeln!(f)
+    }
+
+    /// The marker starting `street`, with the cards dealt for it.
+    fn write_street(&self, f: &mut fmt::Formatter<'_>, street: Street) -> fmt::Result {
+        let (name, before) = match street {
+            Street::Preflop => return Ok(()),
+            Street::Flop => ("FLOP", 0),
+            Street::Turn => ("TURN", 3),
+            Street::River => ("RIVER", 4),
+        };
+        let Some(dealt) = self.board.get(before..board_size(street)) else {
+            return Ok(());
+        };
+        write!(f, "*** {name} *** ")?;
+        if before > 0 {
+            write!(f, "[{}] ", cards(&self.board[..before]))?;
+        }
+        writeln!(f, "[{}]", cards(dealt))
+    }
+}
+
+/// Cards on the board once `street` is dealt.
+fn board_size(street: Street) -> usize {
+    match street {
+        Street::Preflop => 0,
+        Street::Flop => 3,
+        Street::Turn => 4,
+        Street::River => 5,
+    }
+}
+
+fn cards(cards: &[Card]) -> Notated<'_, [Card]> {
+    Notated::new(cards, Notation::Short)
+}
+
+/// An amount in hundredths, written as [`amount`] reads it: `$0.05`, `$2`, `1500`.
+struct Money(u64, Option<char>);
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(symbol) = self.1 {
+            write!(f, "{symbol}")?;
+        }
+        match self.0 % 100 {
+            0 => write!(f, "{}", self.0 / 100),
+            cents => write!(f, "{}.{cents:02}", self.0 / 100),
+        }
+    }
+}
+
+/// Parse every hand in `text`, a whole file as downloaded, where hands are separated by blank lines.
+pub fn parse_all(text: &str) -> impl Iterator<Item = Result<HandHistory, HistoryError>> + '_ {
+    let mut hands = vec![];
+    let mut start = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        match (line.trim().is_empty(), start) {
+            (true, Some(from)) => {
+                hands.push(&text[from..offset]);
+                start = None;
+            }
+            (false, None) => start = Some(offset),
+            _ => {}
+        }
+        offset += line.len();
+    }
+    hands.extend(start.map(|from| &text[from..]));
+    hands.into_iter().map(str::parse)
+}
+
+fn bad(line: usize, text: &str) -> HistoryError {
+    HistoryError::BadLine {
+        line,
+        text: text.to_string(),
+    }
+}
+
+/// An amount such as `$1,000.50`, `€2` or `1500`, in hundredths.
+pub(crate) fn amount(value: &str) -> Option<u64> {
+    let value = value.trim_start_matches(|c: char| !c.is_ascii_digit());
+    let value = value.replace(',', "");
+    let (whole, fraction) = value.split_once('.').unwrap_or((&value, ""));
+    if fraction.len() > 2 || !fraction.bytes().all(|a| a.is_ascii_digit()) {
+        return None;
+    }
+    let fraction = format!("{fraction:0<2}").parse::<u64>().ok()?;
+    Some(whole.parse::<u64>().ok()? * 100 + fraction)
+}
+
+/// Every group of cards in brackets, e.g. `[2c 7h Ts] [Jd]`.
+fn brackets(line: usize, value: &str) -> Result<Vec<Vec<Card>>, HistoryError> {
+    let mut groups = vec![];
+    let mut rest = value;
+    while let Some((_, after)) = rest.split_once('[') {
+        let (inside, after) = after.split_once(']').ok_or_else(|| bad(line, value))?;
+        let cards = parse_cards(inside).map_err(|error| HistoryError::BadCards { line, error })?;
+        groups.push(cards);
+        rest = after;
+    }
+    Ok(groups)
+}
+
+/// `PokerStars Hand #233059016815:  Hold'em No Limit ($0.01/$0.02 USD) - 2021/12/27 20:14:59 ET`
+fn header_line(n: usize, line: &str) -> Result<HandHistory, HistoryError> {
+    let (site, rest) = if let Some(rest) = line.strip_prefix("PokerStars Hand #") {
+        (Site::PokerStars, rest)
+    } else if let Some(rest) = line.strip_prefix("Poker Hand #") {
+        (Site::GgPoker, rest)
+    } else {
+        return Err(bad(n, line));
+    };
+    let parse = || {
+        let (id, rest) = rest.split_once(':')?;
+        let (head, date) = rest.trim_start().rsplit_once(" - ")?;
+        let (game, stakes) = head.rsplit_once(" (")?;
+        let stakes = stakes.strip_suffix(')')?;
+        let (blinds, currency) = match stakes.split_once(' ') {
+            Some((blinds, currency)) => (blinds, Some(currency.to_string())),
+            None => (stakes, None),
+        };
+        let (small, big) = blinds.split_once('/')?;
+        Some(HandHistory {
+            site,
+            id: id.to_string(),
+            game: game.to_string(),
+            small_blind: amount(small)?,
+            big_blind: amount(big)?,
+            symbol: small.chars().next().filter(|a| !a.is_ascii_digit()),
+            currency,
+            date: date.to_string(),
+            table: String::new(),
+            max_seats: 0,
+            button: 0,
+            seats: vec![],
+            hero: None,
+            plays: vec![],
+            board: vec![],
+            uncalled: None,
+            total_pot: 0,
+            rake: 0,
+        })
+    };
+    parse().ok_or_else(|| bad(n, line))
+}
+
+/// `Table 'Aaltje III' 6-max Seat #3 is the button`
+fn table_line(history: &mut HandHistory, n: usize, line: &str) -> Result<(), HistoryError> {
+    let mut parse = || {
+        let (table, rest) = line.strip_prefix("Table '")?.rsplit_once("' ")?;
+        let words = rest.split(' ').collect::<Vec<_>>();
+        let max = words.iter().find_map(|a| a.strip_suffix("-max"))?;
+        let button = words.iter().find_map(|a| a.strip_prefix('#'))?;
+        history.table = table.to_string();
+        history.max_seats = max.parse().ok()?;
+        history.button = button.parse().ok()?;
+        Some(())
+    };
+    parse().ok_or_else(|| bad(n, line))
+}
+
+/// `Seat 1: Alice ($2.05 in chips)`, possibly followed by `is sitting out`.
+fn seat_line(n: usize, line: &str) -> Result<Seat, HistoryError> {
+    let parse = || {
+        let (seat, rest) = line.strip_prefix("Seat ")?.split_once(": ")?;
+        let (name, chips) = rest.rsplit_once(" (")?;
+        let (chips, after) = chips.split_once(')')?;
+        let (stack, _) = chips.split_once(" in chips")?;
+        Some(Seat {
+            seat: seat.parse().ok()?,
+            name: name.to_string(),
+            stack: amount(stack)?,
+            hole: None,
+            shown: false,
+            won: 0,
+            sitting_out: after.contains("sitting out"),
+        })
+    };
+    parse().ok_or_else(|| bad(n, line))
+}
+
+/// `Total pot $0.30 | Rake $0.01`; other summary lines repeat what the hand already says.
+fn summary_line(history: &mut HandHistory, n: usize, line: &str) -> Result<(), HistoryError> {
+    let Some(rest) = line.strip_prefix("Total pot ") else {
+        return Ok(());
+    };
+    let mut parse = || {
+        history.total_pot = amount(rest.split(' ').next()?)?;
+        let (_, rake) = rest.split_once("| Rake ")?;
+        history.rake = amount(rake.split(' ').next()?)?;
+        Some(())
+    };
+    parse().ok_or_else(|| bad(n, line))
+}
+
+/// The player whose name, followed by `separator`, starts `line`; the longest name wins.
+fn player<'a>(history: &HandHistory, line: &'a str, separator: &str) -> Option<(usize, &'a str)> {
+    (history.seats.iter().enumerate())
+        .filter_map(|(i, a)| {
+            let rest = line
+                .strip_prefix(a.name.as_str())?
+                .strip_prefix(separator)?;
+            Some((i, rest, a.name.len()))
+        })
+        .max_by_key(|a| a.2)
+        .map(|a| (a.0, a.1))
+}
+
+fn body_line(
+    history: &mut HandHistory,
+    street: Street,
+    n: usize,
+    line: &str,
+) -> Result<(), HistoryError> {
+    if let Some(rest) = line.strip_prefix("Dealt to ") {
+        // GGPoker also lists the other players, without cards
+        if let Some((i, cards)) = player(history, rest, " [") {
+            let cards = brackets(n, &format!("[{cards}"))?;
+            history.seats[i].hole = cards.into_iter().next();
+            history.hero = Some(history.seats[i].name.clone());
+        }
+        return Ok(());
+    }
+    if let Some(rest) = line.strip_prefix("Uncalled bet (") {
+        let parse = || {
+            let (value, name) = rest.split_once(") returned to ")?;
+            Some((name.to_string(), amount(value)?))
+        };
+        history.uncalled = Some(parse().ok_or_else(|| bad(n, line))?);
+        return Ok(());
+    }
+    if let Some((i, rest)) = player(history, line, " collected ") {
+        let value = rest.split(' ').next().and_then(amount);
+        history.seats[i].won += value.ok_or_else(|| bad(n, line))?;
+        return Ok(());
+    }
+    let Some((i, rest)) = player(history, line, ": ") else {
+        return match line.split_once(": ") {
+            Some((name, rest)) if action(rest).is_some() => Err(HistoryError::UnknownPlayer {
+                line: n,
+                name: name.to_string(),
+            }),
+            _ => Ok(()),
+        };
+    };
+    if let Some(rest) = rest.strip_prefix("shows ") {
+        history.seats[i].hole = brackets(n, rest)?.into_iter().next();
+        history.seats[i].shown = true;
+        return Ok(());
+    }
+    if rest.starts_with("mucks")
+        || rest.starts_with("doesn't show")
+        || rest.contains("sitting out")
+        || STATUS.iter().any(|a| rest.starts_with(a))
+    {
+        return Ok(());
+    }
+    let (action, all_in) = action(rest).ok_or_else(|| bad(n, line))?;
+    let action = action.ok_or_else(|| bad(n, line))?;
+    history.plays.push(Play {
+        street,
+        player: history.seats[i].name.clone(),
+        action,
+        all_in,
+    });
+    Ok(())
+}
+
+/// Player status changes, written after `Name: ` or `Name `, that are not actions.
+const STATUS: [&str; 5] = [
+    "sits out",
+    "sits in",
+    "is disconnected",
+    "is connected",
+    "has timed out",
+];
+
+/// The action in the text after `Name: ` and whether it put the player all-in,
+/// `None` when it is no action at all.
+///
+/// The action itself is `None` when the verb is known but the rest cannot be read.
+fn action(value: &str) -> Option<(Option<Action>, bool)> {
+    let (value, all_in) = match value.strip_suffix(" and is all-in") {
+        Some(value) => (value, true),
+        None => (value, false),
+    };
+    let (verb, rest) = value.split_once(' ').unwrap_or((value, ""));
+    let action = match verb {
+        "folds" => Some(Action::Fold),
+        "checks" => Some(Action::Check),
+        "calls" => amount(rest).map(Action::Call),
+        "bets" => amount(rest).map(Action::Bet),
+        "raises" => rest.split_once(" to ").and_then(|(by, to)| {
+            Some(Action::Raise {
+                by: amount(by)?,
+                to: amount(to)?,
+            })
+        }),
+        "posts" => rest.rsplit_once(' ').and_then(|(kind, value)| {
+            let value = amount(value)?;
+            match kind {
+                "small blind" => Some(Action::SmallBlind(value)),
+                "big blind" => Some(Action::BigBlind(value)),
+                "the ante" => Some(Action::Ante(value)),
+                "small & big blinds" => Some(Action::SmallAndBigBlind(value)),
+                _ => None,
+            }
+        }),
+        _ => return None,
+    };
+    Some((action, all_in))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+
+    const SHOWDOWN: &str = "\
+PokerStars Hand #233059016815:  Hold'em No Limit ($0.01/$0.02 USD) - 2021/12/27 20:14:59 ET
+Table 'Aaltje III' 6-max Seat #3 is the button
+Seat 1: Alice ($2.05 in chips)
+Seat 3: Bob ($2 in chips)
+Seat 4: Carol D. ($1.50 in chips)
+Seat 6: Dan ($3.12 in chips) is sitting out
+Carol D.: posts small blind $0.01
+Alice: posts big blind $0.02
+*** HOLE CARDS ***
+Dealt to Bob [Ah Kd]
+Bob: raises $0.04 to $0.06
+Carol D.: calls $0.05
+Alice: folds
+*** FLOP *** [2c 7h Ts]
+Carol D.: checks
+Bob: bets $0.08
+Carol D.: calls $0.08
+*** TURN *** [2c 7h Ts] [Jd]
+Carol D.: checks
+Bob: checks
+Alice: sits out
+Dan: sits in
+Carol D. is disconnected
+Carol D.: is connected
+Bob has timed out
+*** RIVER *** [2c 7h Ts Jd] [Kc]
+Carol D. said, \"nh\"
+Carol D.: bets $0.20
+Bob: calls $0.20
+*** SHOW DOWN ***
+Carol D.: shows [Qh Qd] (a pair of Queens)
+Bob: shows [Ah Kd] (a pair of Kings)
+Bob collected $0.69 from pot
+*** SUMMARY ***
+Total pot $0.70 | Rake $0.01
+Board [2c 7h Ts Jd Kc]
+Seat 1: Alice (big blind) folded before Flop
+Seat 3: Bob (button) showed [Ah Kd] and won ($0.69) with a pair of Kings
+Seat 4: Carol D. (small blind) showed [Qh Qd] and lost with a pair of Queens
+";
+
+    const FOLDED: &str = "\
+Poker Hand #HD1234567: Hold'em No Limit ($0.05/$0.1) - 2022/01/03 10:00:00
+Table 'RushAndCash 5' 6-max Seat #1 is the button
+Seat 1: Hero ($10 in chips)
+Seat 2: a1b2c3 ($9.90 in chips)
+a1b2c3: posts small blind $0.05
+Hero: posts big blind $0.1
+*** HOLE CARDS ***
+Dealt to Hero [9s 9c]
+Dealt to a1b2c3
+a1b2c3: raises $0.20 to $0.30
+Hero: raises $0.80 to $1.10
+a1b2c3: folds
+Uncalled bet ($0.80) returned to Hero
+Hero collected $0.60 from pot
+Hero: doesn't show hand
+a1b2c3: has timed out
+a1b2c3: is disconnected
+*** SUMMARY ***
+Total pot $0.60 | Rake $0
+Seat 1: Hero (button) collected ($0.60)
+";
+
+    #[test]
+    fn showdown_hand() {
+        let hand: HandHistory = SHOWDOWN.parse().unwrap();
+        assert_eq!(hand.site, Site::PokerStars);
+        assert_eq!(hand.id, "233059016815");
+        assert_eq!(hand.game, "Hold'em No Limit");
+        assert_eq!((hand.small_blind, hand.big_blind), (1, 2));
+        assert_eq!(
+            (hand.symbol, hand.currency.as_deref()),
+            (Some('$'), Some("USD"))
+        );
+        assert_eq!(hand.date, "2021/12/27 20:14:59 ET");
+        assert_eq!(
+            (hand.table.as_str(), hand.max_seats, hand.button),
+            ("Aaltje III", 6, 3)
+        );
+        let names = hand
+            .seats
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Alice", "Bob", "Carol D.", "Dan"]);
+        assert!(hand.seats[3].sitting_out);
+        assert_eq!(hand.seats[2].stack, 150);
+        assert_eq!(hand.hero.as_deref(), Some("Bob"));
+        assert_eq!(hand.board.len(), 5);
+        assert_eq!(hand.plays.len(), 12);
+        assert_eq!(
+            hand.plays[2],
+            Play {
+                street: Street::Preflop,
+                player: "Bob".to_string(),
+                action: Action::Raise { by: 4, to: 6 },
+                all_in: false
+            }
+        );
+        assert_eq!(hand.plays[11].street, Street::River);
+        assert_eq!(hand.seats[1].won, 69);
+        assert!(hand.seats[2].shown);
+        assert_eq!((hand.total_pot, hand.rake), (70, 1));
+        assert_eq!(hand.contributions(), Ok(vec![2, 34, 34, 0]));
+        assert_eq!(hand.verify(), Ok(()));
+    }
+
+    #[test]
+    fn gg_hand_without_showdown() {
+        let hand: HandHistory = FOLDED.parse().unwrap();
+        assert_eq!(hand.site, Site::GgPoker);
+        assert_eq!((hand.symbol, &hand.currency), (Some('$'), &None));
+        assert_eq!(hand.uncalled, Some(("Hero".to_string(), 80)));
+        assert_eq!(hand.contributions(), Ok(vec![30, 30]));
+        assert_eq!(hand.seats[0].hole, Some(parse_cards("9S 9C").unwrap()));
+        assert_eq!(hand.seats[1].hole, None);
+        assert!(!hand.seats[0].shown);
+        assert_eq!(hand.verify(), Ok(()));
+    }
+
+    #[test]
+    fn wrong_winner_is_caught() {
+        let text = SHOWDOWN.replace("Bob collected", "Carol D. collected");
+        let hand: HandHistory = text.parse().unwrap();
+        assert_eq!(
+            hand.verify(),
+            Err(HistoryError::WrongWinners {
+                reported: vec!["Carol D.".to_string()],
+                expected: vec!["Bob".to_string()],
+            })
+        );
+        let text = FOLDED.replace("Hero collected $0.60 from pot", "Hero: folds");
+        let hand: HandHistory = text.parse().unwrap();
+        assert_eq!(hand.verify(), Err(HistoryError::EveryoneFolded));
+    }
+
+    #[test]
+    fn amounts_that_do_not_add_up() {
+        let bad = |player: &str| {
+            Err(HistoryError::BadAmount {
+                player: player.to_string(),
+            })
+        };
+        let text = SHOWDOWN.replace(
+            "*** SHOW DOWN ***",
+            "Uncalled bet ($5) returned to Bob\n*** SHOW DOWN ***",
+        );
+        assert_eq!(text.parse::<HandHistory>(), bad("Bob"));
+        let text = FOLDED.replace("($0.80) returned", "($8) returned");
+        assert_eq!(text.parse::<HandHistory>(), bad("Hero"));
+        let text = FOLDED.replace("raises $0.20 to $0.30", "raises $0.20 to $0.01");
+        assert_eq!(text.parse::<HandHistory>(), bad("a1b2c3"));
+        let text = FOLDED.replace(
+            "Hero: posts big blind $0.1",
+            "Hero: posts small & big blinds $0.05",
+        );
+        assert_eq!(text.parse::<HandHistory>(), bad("Hero"));
+    }
+
+    #[test]
+    fn many_hands_in_a_file() {
+        let text = format!("\u{feff}{SHOWDOWN}\n\n\n{FOLDED}\n\n");
+        let hands = parse_all(&text).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(hands.len(), 2);
+        assert_eq!(hands[1].id, "HD1234567");
+    }
+
+    #[test]
+    fn errors_carry_the_line() {
+        let text = SHOWDOWN.replace("Bob: bets $0.08", "Bob: bets lots");
+        assert_eq!(
+            text.parse::<HandHistory>(),
+            Err(HistoryError::BadLine {
+                line: 16,
+                text: "Bob: bets lots".to_string()
+            })
+        );
+        let text = SHOWDOWN.replace("Alice: folds", "Eve: folds");
+        assert_eq!(
+            text.parse::<HandHistory>(),
+            Err(HistoryError::UnknownPlayer {
+                line: 13,
+                name: "Eve".to_string()
+            })
+        );
+        let text = SHOWDOWN.replace("[Jd]", "[Jx]");
+        assert!(matches!(
+            text.parse::<HandHistory>(),
+            Err(HistoryError::BadCards { line: 18, .. })
+        ));
+        assert_eq!(
+            "".parse::<HandHistory>(),
+            Err(HistoryError::MissingLine { expected: "header" })
+        );
+    }
+
+    #[test]
+    fn written_hands_read_back() {
+        for text in [SHOWDOWN, FOLDED] {
+            let hand: HandHistory = text.parse().unwrap();
+            let written = hand.to_string();
+            assert_eq!(written.parse::<HandHistory>(), Ok(hand), "{written}");
+        }
+        let written = SHOWDOWN.parse::<HandHistory>().unwrap().to_string();
+        assert!(written.starts_with(
+            "PokerStars Hand #233059016815:  Hold'em No Limit ($0.01/$0.02 USD) - 2021/12/27 20:14:59 ET\n"
+        ));
+        assert!(written.contains("Seat 6: Dan ($3.12 in chips) is sitting out\n"));
+        assert!(written.contains("Bob: raises $0.04 to $0.06\n"));
+        assert!(written.contains("*** RIVER *** [2c 7h Ts Jd] [Kc]\n"));
+        assert!(written.contains("Bob: shows [Ah Kd] (Pair of kings)\n"));
+        assert!(written.ends_with("Total pot $0.70 | Rake $0.01\nBoard [2c 7h Ts Jd Kc]\n"));
+        let written = FOLDED.parse::<HandHistory>().unwrap().to_string();
+        assert!(written.starts_with("Poker Hand #HD1234567: Hold'em No Limit ($0.05/$0.10) - "));
+        assert!(written.contains("Uncalled bet ($0.80) returned to Hero\n"));
+    }
+
+    #[test]
+    fn amounts() {
+        assert_eq!(amount("$0.01"), Some(1));
+        assert_eq!(amount("€2"), Some(200));
+        assert_eq!(amount("1,500"), Some(150000));
+        assert_eq!(amount("$1.5"), Some(150));
+        assert_eq!(amount("0.001"), None);
+        assert_eq!(amount("lots"), None);
+    }
+}
@@ -0,0 +1,136 @@
+//! Texas Hold'em: the best five cards out of two hole cards and up to five board cards.
+//!
+//! See [`game`] to play complete hands with betting.
+
+pub mod game;
+
+use crate::{
+    best_indices,
+    card::{check_duplicates, parse_unseen},
+    Card, Hand, ParseError,
+};
+use itertools::Itertools;
+
+/// Best five-card hand out of 5, 6 or 7 distinct cards.
+///
+/// The returned hand has its value cached, so calling [`Hand::evaluate`] on it is free.
+pub fn best_hand(cards: &[Card]) -> Result<Hand, ParseError> {
+    ParseError::check_count(Hand::SIZE..=7, cards.len())?;
+    check_duplicates(cards)?;
+    Ok(best_of(cards))
+}
+
+/// Best five-card hand out of at least five cards, without validation.
+pub(crate) fn best_of(cards: &[Card]) -> Hand {
+    cards
+        .iter()
+        .copied()
+        .combinations(Hand::SIZE)
+        .map(Hand::from_cards)
+        .max_by_key(Hand::evaluate)
+        .expect("at least five cards")
+}
+
+/// Given a shared board of 3 to 5 cards and each player's two hole cards,
+/// return the hole cards of the player(s) holding the best hand.
+///
+/// ```
+/// let winners = poker::holdem::winning_hands("2H 7C 9D JS KS", &["AS AD", "QS 10S", "KH 2D"]);
+/// assert_eq!(winners, Ok(vec!["QS 10S"]));
+/// ```
+///
+/// As with [`crate::winning_hands`], the winners are the very references passed in.
+/// Errors in the board are reported as [`ParseError::InvalidBoard`],
+/// errors in hole cards (including cards already on the board or in an earlier hand)
+/// as [`ParseError::InvalidHand`].
+pub fn winning_hands<'a>(board: &str, holes: &[&'a str]) -> Result<Vec<&'a str>, ParseError> {
+    let board = parse_unseen(board, 3..=5, &[]).map_err(ParseError::in_board)?;
+
+    let mut seen = board.clone();
+    let mut values = Vec::with_capacity(holes.len());
+    for (i, hole) in holes.iter().enumerate() {
+        let cards = parse_unseen(hole, 2..=2, &seen).map_err(|e| e.in_hand(i))?;
+        seen.extend(&cards);
+        let cards = board.iter().chain(&cards).copied().collect::<Vec<_>>();
+        values.push(best_of(&cards).evaluate());
+    }
+
+    Ok(best_indices(&values)
+        .into_iter()
+        .map(|i| holes[i])
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{card::parse_cards, Hands, Rank};
+
+    fn cards(value: &str) -> Vec<Card> {
+        parse_cards(value).unwrap()
+    }
+
+    #[test]
+    fn best_of_seven() {
+        let hand = best_hand(&cards("2H 7C 9D JS KS QS 10S")).unwrap();
+        assert_eq!(hand.evaluate().category(), Hands::Straight);
+        assert_eq!(hand.evaluate().ranks()[0], Rank::King);
+
+        let hand = best_hand(&cards("2S 7S 9D JS KS QS 10S")).unwrap();
+        assert_eq!(hand.evaluate().category(), Hands::Flush);
+        assert_eq!(hand.cards().len(), 5);
+    }
+
+    #[test]
+    fn best_hand_rejects_bad_input() {
+        assert_eq!(
+            best_hand(&cards("2H 7C 9D JS")).unwrap_err(),
+            ParseError::WrongCardCount {
+                expected: 5..=7,
+                found: 4
+            }
+        );
+        let mut dup = cards("2H 7C 9D JS KS");
+        dup.push(dup[1]);
+        assert!(matches!(
+            best_hand(&dup),
+            Err(ParseError::DuplicateCard { offset: 15, .. })
+        ));
+    }
+
+    #[test]
+    fn board_plays() {
+        assert_eq!(
+            winning_hands("10H JH QH KH AH", &["2C 3C", "4D 5D"]),
+            Ok(vec!["2C 3C", "4D 5D"])
+        );
+    }
+
+    #[test]
+    fn kicker_from_hole_cards() {
+        assert_eq!(
+            winning_hands("AH AD 7C 8S 2D", &["KC 3C", "QD 3D"]),
+            Ok(vec!["KC 3C"])
+        );
+    }
+
+    #[test]
+    fn hole_card_on_board() {
+        assert_eq!(
+            winning_hands("AH AD 7C 8S 2D", &["KC 3C", "QD 7C"]),
+            Err(ParseError::DuplicateCard {
+                offset: 3,
+                card: "7C".to_string()
+            }
+            .in_hand(1))
+        );
+        assert_eq!(
+            winning_hands("AH AD", &["KC 3C"]),
+            Err(ParseError::WrongCardCount {
+                expected: 3..=5,
+                found: 2
+            }
+            .in_board())
+        );
+    }
+}
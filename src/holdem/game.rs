@@ -0,0 +1,636 @@
+//! A complete hand of no-limit Texas Hold'em, as a pure state machine.
+//!
+//! Every [`State::apply`] returns a new state and leaves the old one untouched,
+//! so a game tree can be explored by cloning nothing more than the states themselves.
+//!
+//! ```
+//! use poker::{deck::Deck, holdem::game::{Action, Stakes, State, Street}};
+//!
+//! let mut deck = Deck::new();
+//! deck.shuffle_seeded(7);
+//! let state = State::new(Stakes::new(1, 2), &[100, 100], 0, deck).unwrap();
+//! // heads-up, the button posts the small blind and acts first before the flop
+//! assert_eq!(state.to_act(), Some(0));
+//! let state = state.apply(Action::Call).unwrap();
+//! let state = state.apply(Action::Check).unwrap();
+//! assert_eq!(state.street(), Street::Flop);
+//! assert_eq!(state.board().len(), 3);
+//! ```
+
+use super::best_of;
+use crate::{
+    deck::Deck,
+    pot::{self, OddChip},
+    Card, DeckError, HandValue, IllegalAction, SetupError,
+};
+use std::ops::RangeInclusive;
+
+/// Blinds posted at the start of every hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Stakes {
+    pub small_blind: u64,
+    pub big_blind: u64,
+}
+
+impl Stakes {
+    pub fn new(small_blind: u64, big_blind: u64) -> Self {
+        Self {
+            small_blind,
+            big_blind,
+        }
+    }
+}
+
+/// Betting round, named after the cards dealt before it.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Street {
+    pub(crate) fn next(self) -> Self {
+        match self {
+            Street::Preflop => Street::Flop,
+            Street::Flop => Street::Turn,
+            Street::Turn | Street::River => Street::River,
+        }
+    }
+}
+
+/// Something the player to act can do.
+///
+/// The amount of a bet or raise is the player's total for the street, i.e. "raise to", not "raise by".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    Bet(u64),
+    Raise(u64),
+}
+
+/// One seat at the table.
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub hole: [Card; 2],
+    /// Chips behind, not counting what the player has put in.
+    pub stack: u64,
+    /// Chips put in on the current street.
+    pub street_bet: u64,
+    /// Chips put in over the whole hand.
+    pub contributed: u64,
+    pub folded: bool,
+    /// Still to act in the current betting round.
+    needs_to_act: bool,
+    /// False once the player acted and only an incomplete all-in raise followed.
+    can_raise: bool,
+}
+
+impl Player {
+    pub fn is_all_in(&self) -> bool {
+        !self.folded && self.stack == 0
+    }
+
+    fn can_act(&self) -> bool {
+        !self.folded && self.stack > 0
+    }
+}
+
+/// What the player to act may do; folding is always allowed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegalActions {
+    pub check: bool,
+    /// Chips the player would add by calling, less than the bet when calling all-in.
+    pub call: Option<u64>,
+    /// Totals the player may bet to, the top being all-in.
+    pub bet: Option<RangeInclusive<u64>>,
+    /// Totals the player may raise to, the top being all-in.
+    pub raise: Option<RangeInclusive<u64>>,
+}
+
+/// How a complete hand was settled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// Chips awarded to each player, including their own uncalled bets.
+    pub payouts: Vec<u64>,
+    /// Value of each hand at showdown, `None` for folded players
+    /// and for everyone when the hand ended without a showdown.
+    pub shown: Vec<Option<HandValue>>,
+}
+
+/// A hand in progress, or complete once [`State::outcome`] is set.
+#[derive(Debug, Clone)]
+pub struct State {
+    stakes: Stakes,
+    button: usize,
+    players: Vec<Player>,
+    deck: Deck,
+    board: Vec<Card>,
+    street: Street,
+    to_act: Option<usize>,
+    /// Highest total put in on the current street.
+    current_bet: u64,
+    /// Size of the last full bet or raise, the least the next raise must add.
+    min_raise: u64,
+    /// Every action applied so far, with its street and seat.
+    actions: Vec<(Street, usize, Action)>,
+    outcome: Option<Outcome>,
+}
+
+impl State {
+    /// Seat players clockwise with the given stacks, post the blinds and
+    /// deal two hole cards each from the top of `deck`, starting left of the button.
+    ///
+    /// Heads-up, the button posts the small blind.
+    /// Fails with fewer than two players, an empty stack, a button outside the table, jokers in
+    /// `deck`, or fewer cards than the hole cards, the board and its burns need.
+    pub fn new(
+        stakes: Stakes,
+        stacks: &[u64],
+        button: usize,
+        mut deck: Deck,
+    ) -> Result<Self, SetupError> {
+        let n = stacks.len();
+        if n < 2 {
+            return Err(SetupError::TooFewPlayers { players: n });
+        }
+        if let Some(seat) = stacks.iter().position(|&a| a == 0) {
+            return Err(SetupError::EmptyStack { seat });
+        }
+        if button >= n {
+            return Err(SetupError::BadButton { button, players: n });
+        }
+        if deck.cards().iter().any(Card::is_joker) {
+            return Err(SetupError::Jokers);
+        }
+        let needed = 2 * n + 8;
+        if deck.len() < needed {
+            return Err(DeckError::NotEnoughCards {
+                requested: needed,
+                remaining: deck.len(),
+            }
+            .into());
+        }
+        let cards = deck.deal(2 * n)?;
+        let players = stacks
+            .iter()
+            .enumerate()
+            .map(|(i, &stack)| {
+                let first = (i + n - button - 1) % n;
+                Player {
+                    hole: [cards[first], cards[first + n]],
+                    stack,
+                    street_bet: 0,
+                    contributed: 0,
+                    folded: false,
+                    needs_to_act: true,
+                    can_raise: true,
+                }
+            })
+            .collect();
+        let mut state = Self {
+            stakes,
+            button,
+            players,
+            deck,
+            board: vec![],
+            street: Street::Preflop,
+            to_act: None,
+            current_bet: stakes.big_blind,
+            min_raise: stakes.big_blind,
+            actions: vec![],
+            outcome: None,
+        };
+        let (small, big) = match n {
+            2 => (button, (button + 1) % n),
+            _ => ((button + 1) % n, (button + 2) % n),
+        };
+        state.put(small, stakes.small_blind.min(state.players[small].stack));
+        state.put(big, stakes.big_blind.min(state.players[big].stack));
+        state.advance(big);
+        Ok(state)
+    }
+
+    pub fn stakes(&self) -> Stakes {
+        self.stakes
+    }
+
+    pub fn button(&self) -> usize {
+        self.button
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    pub fn board(&self) -> &[Card] {
+        &self.board
+    }
+
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    /// Seat of the player to act, `None` once the hand is complete.
+    pub fn to_act(&self) -> Option<usize> {
+        self.to_act
+    }
+
+    /// Every action applied so far, with the street and seat it was taken on; blinds are not included.
+    pub fn actions(&self) -> &[(Street, usize, Action)] {
+        &self.actions
+    }
+
+    /// Every chip put in so far.
+    pub fn pot(&self) -> u64 {
+        self.players.iter().map(|a| a.contributed).sum()
+    }
+
+    /// Set once the hand is complete; the players' stacks already include their payouts.
+    pub fn outcome(&self) -> Option<&Outcome> {
+        self.outcome.as_ref()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.outcome.is_some()
+    }
+
+    /// What the player to act may do, `None` once the hand is complete.
+    ///
+    /// A raise must add at least the size of the last full bet or raise, unless it puts the player all-in.
+    /// An all-in raise short of that does not reopen the betting to players who already acted.
+    pub fn legal_actions(&self) -> Option<LegalActions> {
+        let seat = self.to_act?;
+        let player = &self.players[seat];
+        let owed = self.current_bet.saturating_sub(player.street_bet);
+        let max = player.street_bet + player.stack;
+        let others = (self.players.iter().enumerate()).any(|(i, a)| i != seat && a.can_act());
+        let open = player.can_raise && others && max > self.current_bet;
+        let min = (self.current_bet + self.min_raise).min(max);
+        Some(LegalActions {
+            check: owed == 0,
+            call: (owed > 0).then(|| owed.min(player.stack)),
+            bet: (open && self.current_bet == 0).then_some(min..=max),
+            raise: (open && self.current_bet > 0).then_some(min..=max),
+        })
+    }
+
+    /// The state after the player to act takes `action`.
+    pub fn apply(&self, action: Action) -> Result<State, IllegalAction> {
+        let (Some(seat), Some(legal)) = (self.to_act, self.legal_actions()) else {
+            return Err(IllegalAction::HandComplete);
+        };
+        let mut next = self.clone();
+        match action {
+            Action::Fold => next.players[seat].folded = true,
+            Action::Check if legal.check => {}
+            Action::Check => {
+                return Err(IllegalAction::CannotCheck {
+                    to_call: self.current_bet - self.players[seat].street_bet,
+                })
+            }
+            Action::Call => next.put(seat, legal.call.ok_or(IllegalAction::NothingToCall)?),
+            Action::Bet(_) if self.current_bet > 0 => return Err(IllegalAction::AlreadyBet),
+            Action::Raise(_) if self.current_bet == 0 => return Err(IllegalAction::NothingToRaise),
+            Action::Bet(to) | Action::Raise(to) => {
+                let range = legal
+                    .bet
+                    .or(legal.raise)
+                    .ok_or(IllegalAction::RaiseNotAllowed)?;
+                if to < *range.start() {
+                    return Err(IllegalAction::AmountTooSmall {
+                        min: *range.start(),
+                    });
+                }
+                if to > *range.end() {
+                    return Err(IllegalAction::AmountTooLarge { max: *range.end() });
+                }
+                next.raise_to(seat, to);
+            }
+        }
+        next.players[seat].needs_to_act = false;
+        next.actions.push((self.street, seat, action));
+        next.advance(seat);
+        Ok(next)
+    }
+
+    fn put(&mut self, seat: usize, amount: u64) {
+        let player = &mut self.players[seat];
+        player.stack -= amount;
+        player.street_bet += amount;
+        player.contributed += amount;
+    }
+
+    fn raise_to(&mut self, seat: usize, to: u64) {
+        self.put(seat, to - self.players[seat].street_bet);
+        let full = to - self.current_bet >= self.min_raise;
+        if full {
+            self.min_raise = to - self.current_bet;
+        }
+        self.current_bet = to;
+        for (i, player) in self.players.iter_mut().enumerate() {
+            if i == seat || !player.can_act() {
+                continue;
+            }
+            if full {
+                player.needs_to_act = true;
+                player.can_raise = true;
+            } else if !player.needs_to_act {
+                player.needs_to_act = true;
+                player.can_raise = false;
+            }
+        }
+    }
+
+    /// First player clockwise after `from` who still has to act.
+    ///
+    /// Nobody has to act when everyone else is all-in and there is nothing to call.
+    fn next_to_act(&self, from: usize) -> Option<usize> {
+        let n = self.players.len();
+        let able = self.players.iter().filter(|a| a.can_act()).count();
+        (1..=n).map(|i| (from + i) % n).find(|&i| {
+            let player = &self.players[i];
+            player.can_act()
+                && player.needs_to_act
+                && (player.street_bet < self.current_bet || able > 1)
+        })
+    }
+
+    /// Pass the action on from `from`, dealing the next street or settling the hand when the round is over.
+    fn advance(&mut self, from: usize) {
+        if self.players.iter().filter(|a| !a.folded).count() == 1 {
+            return self.finish(false);
+        }
+        if let Some(seat) = self.next_to_act(from) {
+            self.to_act = Some(seat);
+            return;
+        }
+        if self.street == Street::River || self.players.iter().filter(|a| a.can_act()).count() < 2 {
+            while self.street != Street::River {
+                self.deal_street();
+            }
+            return self.finish(true);
+        }
+        self.deal_street();
+        for player in &mut self.players {
+            player.street_bet = 0;
+            player.needs_to_act = player.can_act();
+            player.can_raise = true;
+        }
+        self.current_bet = 0;
+        self.min_raise = self.stakes.big_blind;
+        self.advance(self.button);
+    }
+
+    fn deal_street(&mut self) {
+        let count = match self.street {
+            Street::Preflop => 3,
+            _ => 1,
+        };
+        self.deck.burn().expect("deck size checked in State::new");
+        let cards = self
+            .deck
+            .deal(count)
+            .expect("deck size checked in State::new");
+        self.board.extend(cards);
+        self.street = self.street.next();
+    }
+
+    fn finish(&mut self, showdown: bool) {
+        self.to_act = None;
+        let shown = (self.players.iter())
+            .map(|a| {
+                (showdown && !a.folded).then(|| {
+                    let cards = [&a.hole[..], &self.board].concat();
+                    best_of(&cards).evaluate()
+                })
+            })
+            .collect::<Vec<_>>();
+        let contributions = self
+            .players
+            .iter()
+            .map(|a| a.contributed)
+            .collect::<Vec<_>>();
+        let folded = self.players.iter().map(|a| a.folded).collect::<Vec<_>>();
+        let payouts = pot::distribute(
+            &contributions,
+            &folded,
+            &shown,
+            OddChip::LeftOfButton(self.button),
+        )
+        .expect("a player is left in every pot");
+        for (player, payout) in self.players.iter_mut().zip(&payouts) {
+            player.stack += payout;
+        }
+        self.outcome = Some(Outcome { payouts, shown });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hands;
+
+    /// Three players, button on seat 0: seat 1 holds aces, seat 2 kings, seat 0 seven-deuce.
+    fn three_handed(stacks: &[u64]) -> State {
+        let deck = "AH KS 7C AD KD 2D 3C QH 8S 4D 5C 9H 6C JS".parse().unwrap();
+        State::new(Stakes::new(1, 2), stacks, 0, deck).unwrap()
+    }
+
+    fn play(mut state: State, actions: &[Action]) -> State {
+        for &action in actions {
+            state = state
+                .apply(action)
+                .unwrap_or_else(|e| panic!("{action:?}: {e}"));
+        }
+        state
+    }
+
+    fn chips(state: &State) -> u64 {
+        state.players().iter().map(|a| a.stack).sum::<u64>()
+            + if state.is_complete() { 0 } else { state.pot() }
+    }
+
+    #[test]
+    fn blinds_and_first_to_act() {
+        let state = three_handed(&[100, 100, 100]);
+        let posted = state
+            .players()
+            .iter()
+            .map(|a| a.street_bet)
+            .collect::<Vec<_>>();
+        assert_eq!(posted, [0, 1, 2]);
+        assert_eq!(state.to_act(), Some(0));
+        assert_eq!(state.players()[1].hole[0].to_string(), "AH");
+        assert_eq!(state.players()[1].hole[1].to_string(), "AD");
+        // the big blind gets the option after two calls
+        let state = play(state, &[Action::Call, Action::Call]);
+        assert_eq!(state.to_act(), Some(2));
+        let legal = state.legal_actions().unwrap();
+        assert!(legal.check);
+        assert_eq!(legal.raise, Some(4..=100));
+    }
+
+    #[test]
+    fn illegal_actions_and_min_raise() {
+        let state = three_handed(&[100, 100, 100]);
+        let legal = state.legal_actions().unwrap();
+        assert_eq!(
+            legal,
+            LegalActions {
+                check: false,
+                call: Some(2),
+                bet: None,
+                raise: Some(4..=100)
+            }
+        );
+        use IllegalAction::*;
+        assert_eq!(
+            state.apply(Action::Check).unwrap_err(),
+            CannotCheck { to_call: 2 }
+        );
+        assert_eq!(
+            state.apply(Action::Raise(3)).unwrap_err(),
+            AmountTooSmall { min: 4 }
+        );
+        assert_eq!(
+            state.apply(Action::Raise(101)).unwrap_err(),
+            AmountTooLarge { max: 100 }
+        );
+        assert_eq!(state.apply(Action::Bet(10)).unwrap_err(), AlreadyBet);
+        let state = play(state, &[Action::Raise(10)]);
+        assert_eq!(state.legal_actions().unwrap().raise, Some(18..=100));
+        let state = play(state, &[Action::Fold, Action::Call]);
+        assert_eq!(state.street(), Street::Flop);
+        assert_eq!(state.apply(Action::Call).unwrap_err(), NothingToCall);
+        assert_eq!(state.apply(Action::Raise(4)).unwrap_err(), NothingToRaise);
+        assert_eq!(state.legal_actions().unwrap().bet, Some(2..=90));
+    }
+
+    #[test]
+    fn hand_to_showdown() {
+        let state = three_handed(&[100, 100, 100]);
+        let state = play(
+            state,
+            &[
+                Action::Fold,
+                Action::Raise(6),
+                Action::Call,
+                Action::Check,
+                Action::Check,
+                Action::Bet(10),
+                Action::Call,
+            ],
+        );
+        assert_eq!(state.street(), Street::River);
+        assert_eq!(state.to_act(), Some(1));
+        let state = play(state, &[Action::Check, Action::Check]);
+        let outcome = state.outcome().unwrap();
+        assert_eq!(outcome.payouts, [0, 32, 0]);
+        assert_eq!(outcome.shown[0], None);
+        assert_eq!(
+            outcome.shown[1].as_ref().unwrap().category(),
+            Hands::OnePair
+        );
+        let stacks = state.players().iter().map(|a| a.stack).collect::<Vec<_>>();
+        assert_eq!(stacks, [100, 116, 84]);
+        assert_eq!(
+            state.apply(Action::Check).unwrap_err(),
+            IllegalAction::HandComplete
+        );
+    }
+
+    #[test]
+    fn everyone_folds() {
+        let deck = Deck::new();
+        let state = State::new(Stakes::new(1, 2), &[100, 100], 0, deck).unwrap();
+        let state = play(state, &[Action::Fold]);
+        let outcome = state.outcome().unwrap();
+        assert_eq!(outcome.payouts, [0, 3]);
+        assert!(outcome.shown.iter().all(Option::is_none));
+        assert!(state.board().is_empty());
+    }
+
+    #[test]
+    fn incomplete_all_in_raise_and_side_pot() {
+        // seat 2 has the aces this time, and only 13 chips
+        let deck = "KS AH 7C KD AD 2D 3C QH 8S 4D 5C 9H 6C JS".parse().unwrap();
+        let state = State::new(Stakes::new(1, 2), &[100, 100, 13], 0, deck).unwrap();
+        let state = play(state, &[Action::Raise(10), Action::Call, Action::Raise(13)]);
+        // a raise of 3 is short of the last raise of 8, so seat 0 may only call or fold
+        assert_eq!(state.to_act(), Some(0));
+        let legal = state.legal_actions().unwrap();
+        assert_eq!((legal.call, legal.raise), (Some(3), None));
+        let state = play(state, &[Action::Call, Action::Call]);
+        assert_eq!(state.street(), Street::Flop);
+        assert!(state.players()[2].is_all_in());
+        let state = play(
+            state,
+            &[
+                Action::Bet(20),
+                Action::Call,
+                Action::Check,
+                Action::Check,
+                Action::Check,
+                Action::Check,
+            ],
+        );
+        assert_eq!(state.outcome().unwrap().payouts, [0, 40, 39]);
+        assert_eq!(chips(&state), 213);
+    }
+
+    #[test]
+    fn all_in_runs_out_the_board() {
+        let mut deck = Deck::new();
+        deck.shuffle_seeded(3);
+        let state = State::new(Stakes::new(1, 2), &[50, 80], 0, deck).unwrap();
+        assert_eq!(chips(&state), 130);
+        let state = play(state, &[Action::Raise(50), Action::Call]);
+        assert!(state.is_complete());
+        assert_eq!(state.board().len(), 5);
+        assert_eq!(state.street(), Street::River);
+        assert_eq!(state.outcome().unwrap().payouts.iter().sum::<u64>(), 100);
+        assert_eq!(chips(&state), 130);
+    }
+
+    #[test]
+    fn short_deck_is_rejected() {
+        let deck: Deck = "AH KS 7C AD".parse().unwrap();
+        assert_eq!(
+            State::new(Stakes::new(1, 2), &[100, 100], 0, deck).unwrap_err(),
+            SetupError::Deck(DeckError::NotEnoughCards {
+                requested: 12,
+                remaining: 4
+            })
+        );
+    }
+
+    #[test]
+    fn bad_setups_are_rejected() {
+        let new = |stacks: &[u64], button, deck: Deck| {
+            State::new(Stakes::new(1, 2), stacks, button, deck).unwrap_err()
+        };
+        assert_eq!(
+            new(&[100], 0, Deck::new()),
+            SetupError::TooFewPlayers { players: 1 }
+        );
+        assert_eq!(
+            new(&[100, 0, 100], 0, Deck::new()),
+            SetupError::EmptyStack { seat: 1 }
+        );
+        assert_eq!(
+            new(&[100, 100], 2, Deck::new()),
+            SetupError::BadButton {
+                button: 2,
+                players: 2
+            }
+        );
+        assert_eq!(
+            new(&[100, 100], 0, Deck::new().with_jokers(1)),
+            SetupError::Jokers
+        );
+    }
+}
@@ -0,0 +1,470 @@
+//! Suit isomorphism: hands that differ only by a relabelling of suits play exactly alike.
+//!
+//! An [`Indexer`] maps cards dealt over several rounds, such as two hole cards and a
+//! three card flop, to a dense index shared by every suit-isomorphic hand, and maps the
+//! index back to a canonical representative. That is enough to key precomputed tables
+//! and card abstractions by hand class rather than by hand.
+//!
+//! ```
+//! use poker::{isomorphism::Indexer, holdem::game::Street, Card};
+//!
+//! let flop = Indexer::holdem(Street::Flop);
+//! assert_eq!(flop.size(), 1_286_792);
+//! let cards = |a: &str| a.split(' ').map(|a| a.parse().unwrap()).collect::<Vec<Card>>();
+//! // the same hand with hearts and spades swapped
+//! let a = flop.index(&cards("AH KH 2H 7S TC")).unwrap();
+//! let b = flop.index(&cards("AS KS 2S 7H TC")).unwrap();
+//! assert_eq!(a, b);
+//! // clubs go to the suit of the hole cards, then the others by rank
+//! let canonical = flop.unindex(a).unwrap();
+//! assert_eq!(canonical.iter().map(|a| a.to_string()).collect::<Vec<_>>(), ["AC", "KC", "10D", "7H", "2C"]);
+//! ```
+
+use crate::{
+    card::{check_duplicates, written_offset},
+    holdem::game::Street,
+    Card, ParseError, Rank, Suit,
+};
+use std::collections::HashMap;
+
+/// Cards of one suit in each round.
+type Pattern = Vec<u8>;
+
+/// Dense indexing of suit-isomorphic hands dealt over fixed rounds.
+#[derive(Debug, Clone)]
+pub struct Indexer {
+    rounds: Vec<usize>,
+    /// Every way the suits can split the rounds' cards, in index order.
+    configurations: Vec<Configuration>,
+    lookup: HashMap<Vec<Pattern>, usize>,
+}
+
+/// The patterns of the four suits, most cards first, and the indices they cover.
+#[derive(Debug, Clone)]
+struct Configuration {
+    patterns: Vec<Pattern>,
+    /// Runs of suits with the same pattern, whose rank sets are interchangeable.
+    groups: Vec<Group>,
+    offset: u64,
+    size: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Group {
+    /// Position of the first suit of the run.
+    start: usize,
+    len: usize,
+    /// Rank sets one suit with this pattern can hold.
+    values: u64,
+    /// Multisets of `len` such rank sets.
+    size: u64,
+}
+
+impl Indexer {
+    /// An indexer for hands dealt in rounds of the given sizes, e.g. `[2, 3]` for hole cards and a flop.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rounds hold more than 52 cards.
+    pub fn new(rounds: &[usize]) -> Self {
+        assert!(rounds.iter().sum::<usize>() <= 52, "more cards than a deck");
+        let patterns = patterns(rounds);
+        let mut configurations = vec![];
+        let mut chosen = vec![];
+        configure(&patterns, 0, rounds.to_vec(), &mut chosen, &mut |a| {
+            configurations.push(a.to_vec())
+        });
+
+        let mut offset = 0;
+        let configurations = (configurations.into_iter())
+            .map(|patterns: Vec<Pattern>| {
+                let mut groups = Vec::<Group>::new();
+                for (i, pattern) in patterns.iter().enumerate() {
+                    match groups.last_mut() {
+                        Some(group) if patterns[group.start] == *pattern => group.len += 1,
+                        _ => groups.push(Group {
+                            start: i,
+                            len: 1,
+                            values: values(pattern),
+                            size: 0,
+                        }),
+                    }
+                }
+                for group in &mut groups {
+                    group.size = choose(group.values + group.len as u64 - 1, group.len as u64);
+                }
+                let size = groups.iter().map(|a| a.size).product();
+                let configuration = Configuration {
+                    patterns,
+                    groups,
+                    offset,
+                    size,
+                };
+                offset += size;
+                configuration
+            })
+            .collect::<Vec<_>>();
+        let lookup = (configurations.iter().enumerate())
+            .map(|(i, a)| (a.patterns.clone(), i))
+            .collect();
+        Self {
+            rounds: rounds.to_vec(),
+            configurations,
+            lookup,
+        }
+    }
+
+    /// The Hold'em indexer for the cards known on `street`: the 2 hole cards, then the board
+    /// as a single round, since it makes no difference which board card came on which street.
+    pub fn holdem(street: Street) -> Self {
+        match street {
+            Street::Preflop => Self::new(&[2]),
+            Street::Flop => Self::new(&[2, 3]),
+            Street::Turn => Self::new(&[2, 4]),
+            Street::River => Self::new(&[2, 5]),
+        }
+    }
+
+    /// Cards dealt in each round.
+    pub fn rounds(&self) -> &[usize] {
+        &self.rounds
+    }
+
+    /// Number of hand classes; every index is below it.
+    pub fn size(&self) -> u64 {
+        self.configurations.last().map_or(0, |a| a.offset + a.size)
+    }
+
+    /// Index of the class of `cards`, the rounds' cards in order; order within a round does not matter.
+    ///
+    /// Fails on the wrong number of cards, a repeated card or a joker.
+    pub fn index(&self, cards: &[Card]) -> Result<u64, ParseError> {
+        let count = self.rounds.iter().sum::<usize>();
+        ParseError::check_count(count..=count, cards.len())?;
+        if let Some(i) = cards.iter().position(Card::is_joker) {
+            return Err(ParseError::Joker {
+                offset: written_offset(cards, i),
+            });
+        }
+        check_duplicates(cards)?;
+
+        // each suit's ranks in each round
+        let mut masks = [(); 4].map(|_| vec![0u16; self.rounds.len()]);
+        let mut dealt = cards.iter();
+        for (round, &size) in self.rounds.iter().enumerate() {
+            for card in dealt.by_ref().take(size) {
+                masks[suit_index(card.suit())][round] |= 1 << (card.rank().value() - 2);
+            }
+        }
+        let mut suits = masks
+            .iter()
+            .map(|masks| {
+                let pattern = masks
+                    .iter()
+                    .map(|a| a.count_ones() as u8)
+                    .collect::<Pattern>();
+                (pattern, suit_value(masks))
+            })
+            .collect::<Vec<_>>();
+        suits.sort_unstable_by(|a, b| b.cmp(a));
+
+        let patterns = suits.iter().map(|a| a.0.clone()).collect::<Vec<_>>();
+        let configuration = &self.configurations[self.lookup[&patterns]];
+        let mut index = 0;
+        let mut radix = 1;
+        for group in &configuration.groups {
+            let mut values = (suits[group.start..][..group.len].iter())
+                .map(|a| a.1)
+                .collect::<Vec<_>>();
+            values.sort_unstable();
+            index += radix * multiset_rank(&values);
+            radix *= group.size;
+        }
+        Ok(configuration.offset + index)
+    }
+
+    /// The canonical representative of class `index`, `None` if there is no such class.
+    ///
+    /// Suits are handed out clubs, diamonds, hearts, spades, to the suits with the most cards
+    /// in the earliest rounds first, and each round's cards are listed highest rank first.
+    pub fn unindex(&self, index: u64) -> Option<Vec<Card>> {
+        let at = (self.configurations).partition_point(|a| a.offset + a.size <= index);
+        let configuration = self.configurations.get(at)?;
+        let mut rest = index - configuration.offset;
+        let mut masks = vec![vec![]; 4];
+        for group in &configuration.groups {
+            let values = multiset_unrank(rest % group.size, group.len);
+            rest /= group.size;
+            // highest value first, as the suits were sorted when indexing
+            for (i, value) in values.into_iter().rev().enumerate() {
+                let position = group.start + i;
+                masks[position] = suit_masks(value, &configuration.patterns[position]);
+            }
+        }
+        let mut cards = vec![];
+        for round in 0..self.rounds.len() {
+            let mut dealt = (masks.iter().enumerate())
+                .flat_map(|(position, masks)| {
+                    let mask = masks[round];
+                    (0..13)
+                        .filter(move |r| mask >> r & 1 == 1)
+                        .map(move |r| (r, position))
+                })
+                .collect::<Vec<_>>();
+            dealt.sort_unstable_by_key(|&(r, position)| (std::cmp::Reverse(r), position));
+            cards.extend(
+                (dealt.into_iter())
+                    .map(|(r, position)| Card::new(Rank::ALL[r], Suit::ALL[position])),
+            );
+        }
+        Some(cards)
+    }
+
+    /// The canonical representative of the class of `cards`, see [`Indexer::unindex`].
+    pub fn canonical(&self, cards: &[Card]) -> Result<Vec<Card>, ParseError> {
+        let index = self.index(cards)?;
+        Ok(self.unindex(index).expect("index is in range"))
+    }
+}
+
+fn suit_index(suit: Suit) -> usize {
+    Suit::ALL
+        .iter()
+        .position(|&a| a == suit)
+        .expect("not a joker")
+}
+
+/// Every pattern one suit can have: up to the round's size in each round, 13 cards in all.
+fn patterns(rounds: &[usize]) -> Vec<Pattern> {
+    let mut patterns = vec![vec![]];
+    for &size in rounds {
+        patterns = (patterns.into_iter())
+            .flat_map(|pattern: Pattern| {
+                let used = pattern.iter().map(|&a| a as usize).sum::<usize>();
+                (0..=size.min(13 - used)).map(move |a| [&pattern[..], &[a as u8]].concat())
+            })
+            .collect();
+    }
+    patterns.sort_unstable_by(|a, b| b.cmp(a));
+    patterns
+}
+
+/// Call `found` with every nonincreasing choice of four patterns using exactly `left` cards in each round.
+fn configure(
+    patterns: &[Pattern],
+    from: usize,
+    left: Vec<usize>,
+    chosen: &mut Vec<Pattern>,
+    found: &mut dyn FnMut(&[Pattern]),
+) {
+    if chosen.len() == 4 {
+        if left.iter().all(|&a| a == 0) {
+            found(chosen);
+        }
+        return;
+    }
+    for (i, pattern) in patterns.iter().enumerate().skip(from) {
+        if pattern.iter().zip(&left).any(|(&a, &b)| a as usize > b) {
+            continue;
+        }
+        let left = (left.iter().zip(pattern))
+            .map(|(&a, &b)| a - b as usize)
+            .collect();
+        chosen.push(pattern.clone());
+        configure(patterns, i, left, chosen, found);
+        chosen.pop();
+    }
+}
+
+/// Number of ways one suit can hold cards in the given pattern.
+fn values(pattern: &[u8]) -> u64 {
+    let mut used = 0;
+    let mut count = 1;
+    for &a in pattern {
+        count *= choose(13 - used, a as u64);
+        used += a as u64;
+    }
+    count
+}
+
+/// Index of one suit's rank sets among those with the same pattern; each round's set is
+/// ranked among the ranks not used in earlier rounds.
+fn suit_value(masks: &[u16]) -> u64 {
+    let mut used = 0u16;
+    let mut value = 0;
+    let mut radix = 1;
+    for &mask in masks {
+        let positions = (0..13)
+            .filter(|r| mask >> r & 1 == 1)
+            .map(|r| (!used & ((1 << r) - 1)).count_ones() as u64)
+            .collect::<Vec<_>>();
+        value += radix * set_rank(&positions);
+        radix *= choose(13 - used.count_ones() as u64, positions.len() as u64);
+        used |= mask;
+    }
+    value
+}
+
+/// Inverse of [`suit_value`].
+fn suit_masks(mut value: u64, pattern: &[u8]) -> Vec<u16> {
+    let mut used = 0u16;
+    let mut masks = vec![];
+    for &count in pattern {
+        let total = choose(13 - used.count_ones() as u64, count as u64);
+        let positions = set_unrank(value % total, count as usize);
+        value /= total;
+        let free = (0..13).filter(|r| used >> r & 1 == 0).collect::<Vec<_>>();
+        let mask = (positions.into_iter()).fold(0, |mask, p| mask | 1 << free[p as usize]);
+        masks.push(mask);
+        used |= mask;
+    }
+    masks
+}
+
+/// Colexicographic rank of a strictly increasing set.
+fn set_rank(set: &[u64]) -> u64 {
+    (set.iter().enumerate())
+        .map(|(i, &a)| choose(a, i as u64 + 1))
+        .sum()
+}
+
+/// Inverse of [`set_rank`] for sets of `size` elements.
+fn set_unrank(mut rank: u64, size: usize) -> Vec<u64> {
+    let mut set = vec![0; size];
+    for k in (1..=size as u64).rev() {
+        // the largest element whose binomial still fits
+        let mut high = k;
+        while choose(high, k) <= rank {
+            high *= 2;
+        }
+        let mut low = k - 1;
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+            if choose(middle, k) <= rank {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        rank -= choose(low, k);
+        set[k as usize - 1] = low;
+    }
+    set
+}
+
+/// Rank of a sorted multiset, as the set of its elements each shifted up by its position.
+fn multiset_rank(values: &[u64]) -> u64 {
+    let set = (values.iter().enumerate())
+        .map(|(i, &a)| a + i as u64)
+        .collect::<Vec<_>>();
+    set_rank(&set)
+}
+
+/// Inverse of [`multiset_rank`], smallest first.
+fn multiset_unrank(rank: u64, size: usize) -> Vec<u64> {
+    (set_unrank(rank, size).into_iter().enumerate())
+        .map(|(i, a)| a - i as u64)
+        .collect()
+}
+
+fn choose(n: u64, k: u64) -> u64 {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k);
+    (0..k).fold(1u128, |acc, i| acc * (n - i) as u128 / (i + 1) as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::Deck;
+    use std::collections::HashSet;
+
+    fn cards(value: &str) -> Vec<Card> {
+        value.split(' ').map(|a| a.parse().unwrap()).collect()
+    }
+
+    fn text(cards: &[Card]) -> String {
+        cards
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn class_counts() {
+        let sizes = [Street::Preflop, Street::Flop, Street::Turn, Street::River]
+            .map(|a| Indexer::holdem(a).size());
+        assert_eq!(sizes, [169, 1_286_792, 13_960_050, 123_156_254]);
+    }
+
+    #[test]
+    fn every_preflop_class() {
+        let indexer = Indexer::holdem(Street::Preflop);
+        let deck = Deck::new();
+        let mut seen = HashSet::new();
+        for (i, a) in deck.cards().iter().enumerate() {
+            for b in &deck.cards()[i + 1..] {
+                let index = indexer.index(&[*a, *b]).unwrap();
+                seen.insert(index);
+                let canonical = indexer.unindex(index).unwrap();
+                assert_eq!(indexer.index(&canonical), Ok(index));
+            }
+        }
+        assert_eq!(seen.len(), 169);
+        assert_eq!(indexer.unindex(169), None);
+        // suited hands first, then pairs and offsuit hands mixed
+        let names = [0, 77, 78, 167, 168].map(|i| text(&indexer.unindex(i).unwrap()));
+        assert_eq!(names, ["3C 2C", "AC KC", "2C 2D", "AC KD", "AC AD"]);
+    }
+
+    #[test]
+    fn flop_round_trip() {
+        let indexer = Indexer::holdem(Street::Flop);
+        for index in (0..indexer.size()).step_by(97) {
+            let canonical = indexer.unindex(index).unwrap();
+            assert_eq!(indexer.index(&canonical), Ok(index), "{}", text(&canonical));
+        }
+    }
+
+    #[test]
+    fn isomorphic_hands_share_an_index() {
+        let indexer = Indexer::new(&[2, 3, 1, 1]);
+        let a = indexer.index(&cards("AH 2D 3H 4D 5C 9S 9H")).unwrap();
+        let b = indexer.index(&cards("AS 2C 3S 4C 5D 9H 9S")).unwrap();
+        let c = indexer.index(&cards("2D AH 5C 4D 3H 9S 9H")).unwrap();
+        let d = indexer.index(&cards("AH 2D 3H 4D 5C 9H 9S")).unwrap();
+        assert_eq!((a, a), (b, c));
+        // which suit came on the turn and which on the river tells them apart
+        assert_ne!(a, d);
+        let canonical = indexer.canonical(&cards("AH 2D 3H 4D 5C 9S 9H")).unwrap();
+        assert_eq!(text(&canonical), "AC 2D 5H 4D 3C 9S 9C");
+        // but not once the board is a single round
+        let indexer = Indexer::holdem(Street::River);
+        assert_eq!(
+            indexer.index(&cards("AH 2D 3H 4D 5C 9S 9H")),
+            indexer.index(&cards("AH 2D 3H 4D 5C 9H 9S"))
+        );
+    }
+
+    #[test]
+    fn bad_cards() {
+        let indexer = Indexer::holdem(Street::Flop);
+        assert!(matches!(
+            indexer.index(&cards("AH KH 2H 7S")),
+            Err(ParseError::WrongCardCount { found: 4, .. })
+        ));
+        assert!(matches!(
+            indexer.index(&cards("AH KH 2H 7S AH")),
+            Err(ParseError::DuplicateCard { offset: 12, .. })
+        ));
+        let mut joker = cards("AH KH 2H 7S TC");
+        joker[1] = Card::joker();
+        assert!(matches!(
+            indexer.index(&joker),
+            Err(ParseError::Joker { offset: 3 })
+        ));
+    }
+}
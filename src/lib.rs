@@ -1,8 +1,29 @@
-pub(crate) mod card;
-pub(crate) mod hand;
+mod card;
+pub mod deck;
+pub mod equity;
+mod error;
+mod hand;
+pub mod history;
+pub mod holdem;
+pub mod isomorphism;
+mod json;
+pub mod lookup;
+pub mod low;
+pub mod omaha;
+pub mod outs;
+pub mod pot;
+pub mod range;
+pub mod rules;
+#[cfg(feature = "serde")]
+mod serialize;
+pub mod stud;
+pub mod wild;
 
-use card::{Card, Number};
-use hand::Hand;
+pub use card::{Card, Notated, Notation, Rank, Suit};
+pub use error::{DeckError, HistoryError, IllegalAction, ParseError, PotError, SetupError};
+pub use hand::{compare_explain, evaluate_partial, Hand, HandValue, Hands};
+#[cfg(feature = "serde")]
+pub use serialize::structured;
 
 use itertools::Itertools;
 
@@ -10,38 +31,59 @@ use itertools::Itertools;
 ///
 /// Note the type signature: this function should return _the same_ reference to
 /// the winning hand(s) as were passed in, not reconstructed strings which happen to be equal.
+///
+/// # Panics
+///
+/// Panics if any hand is malformed, see [`try_winning_hands`] for the fallible version.
 pub fn winning_hands<'a>(hands_strs: &[&'a str]) -> Vec<&'a str> {
     // unimplemented!("Out of {hands:?}, which hand wins?")
-    hands_strs
+    try_winning_hands(hands_strs).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// Like [`winning_hands`], but reports the first malformed hand instead of panicking.
+///
+/// The error is always [`ParseError::InvalidHand`], carrying the index of the bad hand.
+pub fn try_winning_hands<'a>(hands_strs: &[&'a str]) -> Result<Vec<&'a str>, ParseError> {
+    let hands = hands_strs
         .iter()
-        .map(|&a| Hand::from(a))
         .enumerate()
-        .sorted_by(|a, b| {
-            a.1.partial_cmp(&b.1)
-                .unwrap_or_else(|| panic!("Err: partial_cmp: a: {a:?}, b: {b:?}"))
-        })
+        .map(|(i, a)| a.parse::<Hand>().map_err(|e| e.in_hand(i)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let values = hands.iter().map(Hand::evaluate).collect::<Vec<_>>();
+    Ok(best_indices(&values)
+        .into_iter()
+        .map(|i| hands_strs[i])
+        .collect())
+}
+
+/// Indices of every maximal value, in input order.
+pub(crate) fn best_indices<T: Ord>(values: &[T]) -> Vec<usize> {
+    values
+        .iter()
+        .enumerate()
+        .sorted_by(|a, b| a.1.cmp(b.1))
         .rev()
-        .fold(Vec::<(usize, Hand)>::new(), |mut acc, x| {
-            if acc.is_empty() || acc.first().unwrap().1.eq(&x.1) {
+        .fold(Vec::<(usize, &T)>::new(), |mut acc, x| {
+            if acc.is_empty() || acc.first().unwrap().1.eq(x.1) {
                 acc.push(x);
             }
             acc
         })
         .into_iter()
         .sorted_by_key(|a| a.0)
-        .map(|a| hands_strs[a.0])
+        .map(|a| a.0)
         .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{card::Suit, hand::Hand};
+    use crate::{card::parse_cards, deck::Deck};
 
     #[test]
     fn from() {
         let a = "4S 5H 5S 5D 5C";
-        let _hand = Hand::from(a);
+        let _hand: Hand = a.parse().unwrap();
 
         // assert_eq!(
         //     hand,
@@ -66,15 +108,69 @@ mod tests {
 
     #[test]
     fn card_eq() {
-        let a = Card {
-            kind: Suit::Clover,
-            num: Number(1),
-        };
-        let b = Card {
-            kind: Suit::Diamond,
-            num: Number(1),
+        let a = Card::new(Rank::Ace, Suit::Club);
+        let b = Card::new(Rank::Ace, Suit::Diamond);
+        assert_ne!(a, b);
+        // rank first, then suit
+        assert!(Card::new(Rank::King, Suit::Spade) < a && a < b);
+        assert_eq!(a.cmp_rank(&b), std::cmp::Ordering::Equal);
+        let cards = std::collections::HashSet::from([a, b, a]);
+        assert_eq!(cards.len(), 2);
+    }
+
+    #[test]
+    fn partial_hands() {
+        let value = |a: &str| evaluate_partial(&parse_cards(a).unwrap());
+        let categories = [
+            "AS",
+            "AS AD",
+            "AS AD 3C 3D",
+            "AS AD AC",
+            "AS AD AC AH",
+            "9S 5S",
+        ]
+        .map(|a| value(a).unwrap().category());
+        assert_eq!(
+            categories,
+            [
+                Hands::HighCard,
+                Hands::OnePair,
+                Hands::TwoPair,
+                Hands::ThreeOfAKind,
+                Hands::FourOfAKind,
+                Hands::HighCard
+            ]
+        );
+        // four to a straight flush is only king high
+        let value = value("KH QH JH 10H").unwrap();
+        assert_eq!(value.category(), Hands::HighCard);
+        assert_eq!(
+            value.ranks(),
+            [Rank::King, Rank::Queen, Rank::Jack, Rank::Ten]
+        );
+        // jokers pair the best card, or are aces on their own
+        let value = |a: &str| {
+            evaluate_partial(&parse_cards(a).unwrap())
+                .unwrap()
+                .describe()
         };
-        assert_eq!(a, b);
+        assert_eq!(value("KH JK"), "Pair of kings");
+        assert_eq!(value("JK JK"), "Pair of aces");
+        assert_eq!(value("JK 5D 5C"), "Three of a kind, fives");
+        // five cards are a whole hand
+        assert_eq!(value("2H 3H 4H 5H 6H"), "Six-high straight flush");
+        assert!(evaluate_partial(&[]).is_err());
+        let ace: Card = "AS".parse().unwrap();
+        assert!(evaluate_partial(&[ace, ace]).is_err());
+    }
+
+    #[test]
+    fn hands_sort() {
+        let mut hands = ["KS KD 4C 4H QS", "2H 3H 4H 5H 6H", "KH KC 4D 4S QD"]
+            .map(|a| a.parse::<Hand>().unwrap());
+        hands.sort();
+        assert_eq!(hands[2].evaluate().category(), Hands::StraightFlush);
+        assert_eq!(hands[0], hands[1]);
     }
 
     #[test]
@@ -86,10 +182,189 @@ mod tests {
         )
     }
 
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "4S 5H 1S 5D 5C".parse::<Hand>(),
+            Err(ParseError::BadRank {
+                offset: 6,
+                rank: "1".to_string()
+            })
+        );
+        assert_eq!(
+            "4S 5H 5X 5D 5C".parse::<Hand>(),
+            Err(ParseError::BadSuit {
+                offset: 7,
+                suit: "X".to_string()
+            })
+        );
+        assert_eq!(
+            "4S 5H  5D 5C".parse::<Hand>(),
+            Err(ParseError::EmptyToken { offset: 6 })
+        );
+        assert_eq!(
+            "4S 10H 5S 10H 5C".parse::<Hand>(),
+            Err(ParseError::DuplicateCard {
+                offset: 10,
+                card: "10H".to_string()
+            })
+        );
+        assert_eq!(
+            "4S 5H 5S 5D".parse::<Hand>(),
+            Err(ParseError::WrongCardCount {
+                expected: 5..=5,
+                found: 4
+            })
+        );
+    }
+
+    #[test]
+    fn try_winning_hands_reports_hand_index() {
+        assert_eq!(
+            try_winning_hands(&["4S 5H 5S 5D 5C", "2S 3S 4S 5S 6S", "2S 3S 4S 5S"]),
+            Err(ParseError::WrongCardCount {
+                expected: 5..=5,
+                found: 4
+            }
+            .in_hand(2))
+        );
+        assert_eq!(
+            try_winning_hands(&["4S 5H 5S 5D 5C", "2S 3S 4S 5S 6S"]),
+            Ok(vec!["2S 3S 4S 5S 6S"])
+        );
+    }
+
     #[test]
     fn number_sort_ord() {
-        let a = Number(1);
-        let b = Number(2);
+        let a = Rank::Ace;
+        let b = Rank::Two;
         assert_eq!(a.cmp(&b), std::cmp::Ordering::Greater);
     }
+
+    #[test]
+    fn evaluate() {
+        let value = "5H 8S 5D 8D 5C".parse::<Hand>().unwrap().evaluate();
+        assert_eq!(value.category(), Hands::FullHouse);
+        assert_eq!(value.ranks(), [Rank::Five, Rank::Eight]);
+
+        let wheel = "4D AH 3S 2D 5C".parse::<Hand>().unwrap().evaluate();
+        let six_high = "2H 3C 4D 5D 6H".parse::<Hand>().unwrap().evaluate();
+        assert_eq!(wheel.category(), Hands::Straight);
+        assert!(wheel < six_high);
+        assert!(value > six_high);
+    }
+
+    #[test]
+    fn describe() {
+        let describe = |a: &str| a.parse::<Hand>().unwrap().describe();
+        assert_eq!(describe("5H 8S 5D 8D 5C"), "Full house, fives over eights");
+        assert_eq!(describe("2H 9H 5H AH 7H"), "Ace-high flush");
+        assert_eq!(describe("4D AH 3S 2D 5C"), "Five-high straight");
+        assert_eq!(describe("10C JC QC KC AC"), "Royal flush");
+        assert_eq!(describe("6C 6D 2H 3S 9S"), "Pair of sixes");
+        assert_eq!(describe("KC 6D 2H 3S 9S"), "King high");
+    }
+
+    #[test]
+    fn compare_explain() {
+        let explain =
+            |a: &str, b: &str| crate::compare_explain(&a.parse().unwrap(), &b.parse().unwrap());
+        assert_eq!(
+            explain("4C 6C 8C 10C QC", "4D 5S 6H 7H 8D"),
+            (
+                std::cmp::Ordering::Greater,
+                "Queen-high flush beats eight-high straight".to_string()
+            )
+        );
+        assert_eq!(
+            explain("4C 4D 4H 9S 9D", "5C 5S 5D 8S 8D").1,
+            "both have full house; decided by three of a kind 4 vs 5"
+        );
+        assert_eq!(
+            explain("AC KD 8H 7S 3D", "AH KS 8C 7D 2D").1,
+            "both have ace high; decided by fifth card 3 vs 2"
+        );
+        assert_eq!(
+            explain("AC KD 8H 7S 3D", "AH KS 8C 7D 3C"),
+            (
+                std::cmp::Ordering::Equal,
+                "both have ace high; split pot".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn compare_explain_partial() {
+        let value = |a: &str| evaluate_partial(&parse_cards(a).unwrap()).unwrap();
+        let (full, partial) = (value("AS AD KC 5H 2D"), value("AH AC"));
+        assert_eq!(
+            full.compare_explain(&partial),
+            (
+                std::cmp::Ordering::Greater,
+                "both have pair of aces; decided by kicker K vs none".to_string()
+            )
+        );
+        assert_eq!(
+            partial.compare_explain(&full),
+            (
+                std::cmp::Ordering::Less,
+                "both have pair of aces; decided by kicker none vs K".to_string()
+            )
+        );
+        let (full, partial) = (value("AS QD 9C 5H 2D"), value("AH QC 9S 5D"));
+        assert_eq!(
+            partial.compare_explain(&full),
+            (
+                std::cmp::Ordering::Less,
+                "both have ace high; decided by fifth card none vs 2".to_string()
+            )
+        );
+        assert_eq!(
+            full.compare_explain(&partial).0,
+            std::cmp::Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn card_display_round_trip() {
+        let card = Card::new(Rank::Ten, Suit::Heart);
+        assert_eq!(card.to_string(), "10H");
+        assert!(card.is_same(&"10H".parse().unwrap()));
+    }
+
+    #[test]
+    fn alternative_notation() {
+        let ten = Card::new(Rank::Ten, Suit::Heart);
+        for a in ["10H", "Th", "th", "10h", "T♥", "t♡", "🂺"] {
+            assert!(ten.is_same(&a.parse().unwrap()), "{a}");
+        }
+        let hand: Hand = "A♠ k♦ 🃞 🂱 JK".parse().unwrap();
+        assert_eq!(
+            hand.display(Notation::Standard).to_string(),
+            "AS KD KC AH JK"
+        );
+        assert_eq!(hand.display(Notation::Short).to_string(), "As Kd Kc Ah JK");
+        assert_eq!(
+            hand.display(Notation::Symbols).to_string(),
+            "A♠ K♦ K♣ A♥ 🃏"
+        );
+        assert_eq!(hand.display(Notation::PlayingCard).to_string(), "🂡🃎🃞🂱🃏");
+        for card in Deck::new().cards() {
+            for notation in [Notation::Short, Notation::Symbols, Notation::PlayingCard] {
+                let text = card.display(notation).to_string();
+                assert!(card.is_same(&text.parse().unwrap()), "{text}");
+            }
+        }
+        // offsets stay byte offsets with multi-byte suits
+        assert_eq!(
+            "4♠ 5♥ 5X".parse::<Hand>(),
+            Err(ParseError::BadSuit {
+                offset: 11,
+                suit: "X".to_string()
+            })
+        );
+        assert!("🂬".parse::<Card>().is_err(), "knights are not cards");
+        assert_eq!(Rank::Ten.display(Notation::Short).to_string(), "T");
+        assert_eq!(Suit::Spade.display(Notation::Symbols).to_string(), "♠");
+    }
 }
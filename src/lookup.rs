@@ -0,0 +1,388 @@
+//! Table-driven evaluator for high throughput.
+//!
+//! Cards are packed into a [`CompactCard`] and five of them reduce to a single `u16`
+//! strength with a couple of table lookups and no allocation.
+//! The tables are derived once, on first use, from [`Hand::evaluate`],
+//! so both evaluators always agree on the order of hands.
+
+use crate::{Card, Hand, HandValue, ParseError, Rank, Suit};
+use itertools::Itertools;
+use std::sync::OnceLock;
+
+/// Number of distinct five-card high hand values.
+pub const DISTINCT_VALUES: u16 = 7462;
+
+const PRIMES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// A card packed into 32 bits, in the layout popularised by Cactus Kev:
+///
+/// ```text
+/// xxxbbbbb bbbbbbbb cdhsrrrr xxpppppp
+/// ```
+///
+/// `b` is one bit per rank (deuce lowest), `cdhs` one bit per suit,
+/// `r` the rank index (deuce is 0) and `p` the rank's prime (deuce is 2).
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct CompactCard(u32);
+
+impl CompactCard {
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+
+    fn prime(self) -> u32 {
+        self.0 & 0xFF
+    }
+
+    fn rank_bit(self) -> u32 {
+        self.0 >> 16
+    }
+
+    fn suit_bit(self) -> u32 {
+        self.0 & 0xF000
+    }
+}
+
+/// Jokers have no compact form and are handed back as the error.
+impl TryFrom<Card> for CompactCard {
+    type Error = Card;
+
+    fn try_from(card: Card) -> Result<Self, Self::Error> {
+        let suit = match card.suit() {
+            Suit::Club => 0x8000,
+            Suit::Diamond => 0x4000,
+            Suit::Heart => 0x2000,
+            Suit::Spade => 0x1000,
+            Suit::Joker => return Err(card),
+        };
+        let r = card.rank().value() as u32 - 2;
+        Ok(Self((1 << (16 + r)) | suit | (r << 8) | PRIMES[r as usize]))
+    }
+}
+
+impl From<CompactCard> for Card {
+    fn from(card: CompactCard) -> Self {
+        let rank = Rank::ALL[(card.0 >> 8 & 0xF) as usize];
+        let suit = match card.suit_bit() {
+            0x8000 => Suit::Club,
+            0x4000 => Suit::Diamond,
+            0x2000 => Suit::Heart,
+            _ => Suit::Spade,
+        };
+        Card::new(rank, suit)
+    }
+}
+
+struct Tables {
+    /// Strength of a flush, by rank bits.
+    flushes: Vec<u16>,
+    /// Strength of five distinct ranks that are not a flush, by rank bits.
+    unique5: Vec<u16>,
+    /// Strength of every hand with a repeated rank, by product of rank primes, sorted.
+    products: Vec<(u32, u16)>,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        // one representative hand per equivalence class, suits dealt round robin
+        // so that only the explicitly suited ones are flushes
+        let rank_sets = (0..13usize)
+            .combinations_with_replacement(5)
+            .filter(|a| a.iter().counts().values().all(|&n| n <= 4))
+            .collect::<Vec<_>>();
+        let offsuit = |ranks: &[usize]| -> Vec<CompactCard> {
+            ranks
+                .iter()
+                .enumerate()
+                .map(|(i, &r)| compact(Rank::ALL[r], Suit::ALL[i % 4]))
+                .collect()
+        };
+        let suited = |ranks: &[usize]| -> Vec<CompactCard> {
+            ranks
+                .iter()
+                .map(|&r| compact(Rank::ALL[r], Suit::Spade))
+                .collect()
+        };
+        let value = |cards: &[CompactCard]| -> HandValue {
+            Hand::from_cards(cards.iter().map(|&a| a.into()).collect()).evaluate()
+        };
+
+        let flush_sets = rank_sets
+            .iter()
+            .filter(|a| a.iter().all_unique())
+            .collect::<Vec<_>>();
+        let mut classes = rank_sets
+            .iter()
+            .map(|a| offsuit(a))
+            .chain(flush_sets.iter().map(|a| suited(a)))
+            .map(|a| (value(&a), a))
+            .collect::<Vec<_>>();
+        classes.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut tables = Tables {
+            flushes: vec![0; 0x1F01],
+            unique5: vec![0; 0x1F01],
+            products: vec![],
+        };
+        let mut strength = 0;
+        for (i, (value, cards)) in classes.iter().enumerate() {
+            if i == 0 || classes[i - 1].0 != *value {
+                strength += 1;
+            }
+            let bits = rank_bits(cards);
+            if is_flush(cards) {
+                tables.flushes[bits as usize] = strength;
+            } else if bits.count_ones() == 5 {
+                tables.unique5[bits as usize] = strength;
+            } else {
+                tables.products.push((product(cards), strength));
+            }
+        }
+        debug_assert_eq!(strength, DISTINCT_VALUES);
+        tables.products.sort();
+        tables
+    })
+}
+
+fn compact(rank: Rank, suit: Suit) -> CompactCard {
+    Card::new(rank, suit).try_into().unwrap()
+}
+
+fn rank_bits(cards: &[CompactCard]) -> u32 {
+    cards.iter().fold(0, |acc, a| acc | a.rank_bit())
+}
+
+fn is_flush(cards: &[CompactCard]) -> bool {
+    cards.iter().fold(0xF000, |acc, a| acc & a.suit_bit()) != 0
+}
+
+fn product(cards: &[CompactCard]) -> u32 {
+    cards.iter().map(|a| a.prime()).product()
+}
+
+/// Strength of five distinct cards, from 1 (7-5-4-3-2 offsuit) to [`DISTINCT_VALUES`]
+/// (royal flush). Greater is better, and equal strengths split the pot.
+///
+/// Fails with [`ParseError::DuplicateCard`] if a card appears twice.
+pub fn evaluate5(cards: [CompactCard; 5]) -> Result<u16, ParseError> {
+    check_duplicates(&cards)?;
+    Ok(strength5(cards))
+}
+
+/// Strength of the best five of 5, 6 or 7 distinct cards, as [`evaluate5`].
+///
+/// Fails with [`ParseError::WrongCardCount`] for any other number of cards and with
+/// [`ParseError::DuplicateCard`] if a card appears twice.
+pub fn evaluate(cards: &[CompactCard]) -> Result<u16, ParseError> {
+    ParseError::check_count(Hand::SIZE..=7, cards.len())?;
+    check_duplicates(cards)?;
+    Ok(best_strength(cards))
+}
+
+/// [`evaluate`] without validation, for callers that already know the cards are good.
+pub(crate) fn best_strength(cards: &[CompactCard]) -> u16 {
+    let n = cards.len();
+    let mut best = 0;
+    for a in 0..n {
+        for b in a + 1..n {
+            for c in b + 1..n {
+                for d in c + 1..n {
+                    for e in d + 1..n {
+                        let s = strength5([cards[a], cards[b], cards[c], cards[d], cards[e]]);
+                        best = best.max(s);
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+fn strength5(cards: [CompactCard; 5]) -> u16 {
+    let tables = tables();
+    let bits = rank_bits(&cards) as usize;
+    if is_flush(&cards) {
+        return tables.flushes[bits];
+    }
+    match tables.unique5[bits] {
+        0 => {
+            let product = product(&cards);
+            let i = tables
+                .products
+                .binary_search_by_key(&product, |a| a.0)
+                .expect("five distinct cards");
+            tables.products[i].1
+        }
+        a => a,
+    }
+}
+
+/// Fail on the first card seen before, its offset being that of the cards written out
+/// separated by spaces. The text is only built once a duplicate turns up.
+fn check_duplicates(cards: &[CompactCard]) -> Result<(), ParseError> {
+    let Some(i) = (1..cards.len()).find(|&i| cards[..i].contains(&cards[i])) else {
+        return Ok(());
+    };
+    let text = |a: &CompactCard| Card::from(*a).to_string();
+    Err(ParseError::DuplicateCard {
+        offset: cards[..i].iter().map(|a| text(a).len() + 1).sum(),
+        card: text(&cards[i]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+    use std::collections::HashMap;
+
+    fn compact_cards(value: &str) -> Vec<CompactCard> {
+        parse_cards(value)
+            .unwrap()
+            .into_iter()
+            .map(|a| a.try_into().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn encoding() {
+        let card = compact(Rank::King, Suit::Diamond);
+        assert_eq!(card.bits(), 0x0800_4B25);
+        assert!(Card::from(card).is_same(&"KD".parse().unwrap()));
+        assert!(CompactCard::try_from(Card::joker()).is_err());
+    }
+
+    #[test]
+    fn extremes() {
+        assert_eq!(evaluate(&compact_cards("7S 5H 4C 3D 2S")), Ok(1));
+        assert_eq!(
+            evaluate(&compact_cards("AS KS QS JS 10S")),
+            Ok(DISTINCT_VALUES)
+        );
+        assert_eq!(
+            evaluate(&compact_cards("2H 7C 9D JS KS QS 10S")),
+            evaluate(&compact_cards("9D JS KS QS 10S"))
+        );
+    }
+
+    #[test]
+    fn bad_input_is_an_error() {
+        let cards = compact_cards("AS KS QS JS 10S 9S");
+        assert_eq!(
+            evaluate(&cards[..4]),
+            Err(ParseError::WrongCardCount {
+                expected: 5..=7,
+                found: 4
+            })
+        );
+        assert!(evaluate(&[cards.clone(), cards.clone()].concat()).is_err());
+        let ace = cards[0];
+        assert_eq!(
+            evaluate5([cards[1], ace, cards[2], ace, cards[3]]),
+            Err(ParseError::DuplicateCard {
+                offset: 9,
+                card: "AS".to_string()
+            })
+        );
+        assert_eq!(
+            evaluate(&[cards[1], cards[2], cards[3], cards[4], cards[5], cards[2]]),
+            Err(ParseError::DuplicateCard {
+                offset: 16,
+                card: "QS".to_string()
+            })
+        );
+    }
+
+    /// Number of the C(52, 5) hands in each category and of distinct strengths within it,
+    /// from worst to best, as counted by hand rather than by [`Hand::evaluate`].
+    const CATEGORIES: [(usize, u16); 9] = [
+        (1_302_540, 1277),
+        (1_098_240, 2860),
+        (123_552, 858),
+        (54_912, 858),
+        (10_200, 10),
+        (5_108, 1277),
+        (3_744, 156),
+        (624, 156),
+        (40, 10),
+    ];
+
+    #[test]
+    fn category_counts() {
+        let deck = Suit::ALL
+            .into_iter()
+            .cartesian_product(Rank::ALL)
+            .map(|(s, r)| compact(r, s))
+            .collect::<Vec<_>>();
+        // strengths are dealt out category by category, worst first
+        let mut bounds = vec![];
+        let mut top = 0;
+        for (_, classes) in CATEGORIES {
+            top += classes;
+            bounds.push(top);
+        }
+        assert_eq!(top, DISTINCT_VALUES);
+        let mut hands = [0; 9];
+        for a in 0..52 {
+            for b in a + 1..52 {
+                for c in b + 1..52 {
+                    for d in c + 1..52 {
+                        for e in d + 1..52 {
+                            let s = strength5([deck[a], deck[b], deck[c], deck[d], deck[e]]);
+                            hands[bounds.partition_point(|&a| a < s)] += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(hands, CATEGORIES.map(|a| a.0));
+    }
+
+    /// Check every `step`th five-card hand gets the same order from both evaluators.
+    fn check_against_hand_evaluate(step: usize) {
+        let deck = Suit::ALL
+            .into_iter()
+            .cartesian_product(Rank::ALL)
+            .map(|(s, r)| Card::new(r, s))
+            .collect::<Vec<_>>();
+        let compact_deck = deck
+            .iter()
+            .map(|&a| CompactCard::try_from(a).unwrap())
+            .collect::<Vec<_>>();
+
+        let mut seen = HashMap::<u16, HandValue>::new();
+        let mut count = 0;
+        for i in (0..52).combinations(5).step_by(step) {
+            let strength = evaluate5([
+                compact_deck[i[0]],
+                compact_deck[i[1]],
+                compact_deck[i[2]],
+                compact_deck[i[3]],
+                compact_deck[i[4]],
+            ])
+            .unwrap();
+            let value = Hand::from_cards(i.iter().map(|&a| deck[a]).collect()).compute(true);
+            assert_eq!(
+                *seen.entry(strength).or_insert_with(|| value.clone()),
+                value
+            );
+            count += 1;
+        }
+        assert_eq!(count, 2_598_960_usize.div_ceil(step));
+        let by_strength = seen.into_iter().sorted_by_key(|a| a.0).collect::<Vec<_>>();
+        assert!(by_strength.windows(2).all(|a| a[0].1 < a[1].1));
+    }
+
+    #[test]
+    fn agrees_with_hand_evaluate_sampled() {
+        check_against_hand_evaluate(97);
+    }
+
+    /// All 2,598,960 five-card hands; slow without optimisations.
+    #[test]
+    #[ignore = "exhaustive, run with `cargo test --release -- --ignored`"]
+    fn agrees_with_hand_evaluate() {
+        check_against_hand_evaluate(1);
+    }
+}
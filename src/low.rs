@@ -0,0 +1,252 @@
+//! Ace-to-five low hands, as played in lowball, Razz and the low half of hi-lo split games.
+
+use crate::{
+    best_indices,
+    card::{check_no_jokers, parse_unseen},
+    Card, Hand, Hands, ParseError, Rank,
+};
+use itertools::Itertools;
+use std::cmp::{Ordering, Reverse};
+
+/// Strength of an ace-to-five low.
+///
+/// Aces count as one and straights and flushes are ignored, but pairs still count against.
+/// Values compare so that the *better* low is *greater*,
+/// e.g. `5-4-3-2-A > 6-4-3-2-A > 8-7-6-5-4 > A-A-4-3-2`.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct LowValue {
+    category: Hands,
+    ranks: Vec<Rank>,
+}
+
+impl LowValue {
+    /// Ace-to-five value of five cards.
+    pub(crate) fn ace_to_five(cards: &[Card]) -> Self {
+        // most repeated first, then worst first
+        let ranks = cards
+            .iter()
+            .map(|a| a.rank())
+            .counts()
+            .into_iter()
+            .sorted_by_key(|&(rank, count)| Reverse((count, rank.low_value())))
+            .collect::<Vec<_>>();
+        let category = match ranks.iter().map(|a| a.1).collect::<Vec<_>>()[..] {
+            [4, ..] => Hands::FourOfAKind,
+            [3, 2] => Hands::FullHouse,
+            [3, ..] => Hands::ThreeOfAKind,
+            [2, 2, ..] => Hands::TwoPair,
+            [2, ..] => Hands::OnePair,
+            _ => Hands::HighCard,
+        };
+        Self {
+            category,
+            ranks: ranks.into_iter().map(|a| a.0).collect(),
+        }
+    }
+
+    /// Five unpaired cards no higher than an eight, or `None`.
+    pub(crate) fn eight_or_better(cards: &[Card]) -> Option<Self> {
+        Some(Self::ace_to_five(cards)).filter(|a| a.qualifies(Rank::Eight))
+    }
+
+    /// Unpaired and no card above `max`.
+    pub(crate) fn qualifies(&self, max: Rank) -> bool {
+        self.category == Hands::HighCard && self.ranks[0].low_value() <= max.low_value()
+    }
+
+    /// [`Hands::HighCard`] for an unpaired low, otherwise the pairing that spoils it.
+    pub fn category(&self) -> Hands {
+        self.category
+    }
+
+    /// Ranks of the low, paired ranks first and then worst first,
+    /// e.g. `[Six, Four, Three, Two, Ace]`.
+    pub fn ranks(&self) -> &[Rank] {
+        &self.ranks
+    }
+
+    fn key(&self) -> (Hands, Vec<u8>) {
+        (
+            self.category,
+            self.ranks.iter().map(|a| a.low_value()).collect(),
+        )
+    }
+}
+
+impl PartialOrd for LowValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LowValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key().cmp(&self.key())
+    }
+}
+
+/// Winners of a pot split between the best high and the best qualifying low.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HiLo<'a> {
+    /// Hands sharing the high half, or the whole pot when nobody qualifies for low.
+    pub high: Vec<&'a str>,
+    /// Hands sharing the low half; empty when no hand qualifies.
+    pub low: Vec<&'a str>,
+    /// Fraction of the pot won by each input hand, in input order.
+    ///
+    /// e.g. `[0.75, 0.25]` when the first hand wins high and ties for low: it is quartered.
+    pub shares: Vec<f64>,
+}
+
+impl<'a> HiLo<'a> {
+    /// Split between the hands at indices `high` and `low` of `hands`.
+    pub(crate) fn new(hands: &[&'a str], high: &[usize], low: &[usize]) -> Self {
+        Self {
+            high: high.iter().map(|&i| hands[i]).collect(),
+            low: low.iter().map(|&i| hands[i]).collect(),
+            shares: shares(hands.len(), high, low),
+        }
+    }
+}
+
+/// Fraction of the pot won by each of `count` hands when `high` and `low` split it.
+pub(crate) fn shares(count: usize, high: &[usize], low: &[usize]) -> Vec<f64> {
+    let mut shares = vec![0.0; count];
+    let halves = if low.is_empty() { 1.0 } else { 0.5 };
+    for &i in high {
+        shares[i] += halves / high.len() as f64;
+    }
+    for &i in low {
+        shares[i] += halves / low.len() as f64;
+    }
+    shares
+}
+
+/// Which hands may win the low half of a split pot.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum LowRule {
+    /// Five unpaired cards no higher than an eight; with no such hand the high hand scoops.
+    EightOrBetter,
+    /// The best ace-to-five low always wins half, however poor.
+    AnyLow,
+}
+
+/// Split the pot between the best high hand and the best low, for stud and draw games
+/// where each hand plays the best five of its own five to seven cards.
+///
+/// High hands compare as [`Hand`]s do; lows are ace-to-five, see [`LowValue`]. There are no
+/// wild cards, so a joker is an error.
+///
+/// ```
+/// use poker::low::{split_winners, LowRule};
+///
+/// let hands = ["AS 2D 3C 4H 5S KD KC", "KS KH QD QC 9S 8D 7C", "AD 3D 6H 7S 8C JD JS"];
+/// let result = split_winners(&hands, LowRule::EightOrBetter).unwrap();
+/// // the wheel is a straight for high and the best low: a scoop
+/// assert_eq!(result.high, [hands[0]]);
+/// assert_eq!(result.low, [hands[0]]);
+/// assert_eq!(result.shares, [1.0, 0.0, 0.0]);
+/// ```
+pub fn split_winners<'a>(hands: &[&'a str], low_rule: LowRule) -> Result<HiLo<'a>, ParseError> {
+    let mut seen = vec![];
+    let mut cards = Vec::with_capacity(hands.len());
+    for (i, hand) in hands.iter().enumerate() {
+        let hand = parse_unseen(hand, Hand::SIZE..=7, &seen)
+            .and_then(|cards| check_no_jokers(hand, &cards).map(|_| cards))
+            .map_err(|e| e.in_hand(i))?;
+        seen.extend(&hand);
+        cards.push(hand);
+    }
+    let (high, low) = split(&cards, Some(low_rule));
+    Ok(HiLo::new(hands, &high, &low))
+}
+
+/// Indices of the best high hands and, under `low_rule`, of the best lows,
+/// each hand playing the best five of its five to seven cards.
+pub(crate) fn split(cards: &[Vec<Card>], low_rule: Option<LowRule>) -> (Vec<usize>, Vec<usize>) {
+    let high = (cards.iter())
+        .map(|a| {
+            (a.iter().copied().combinations(Hand::SIZE))
+                .map(Hand::from_cards)
+                .max()
+                .expect("at least five cards")
+        })
+        .collect::<Vec<_>>();
+    let Some(low_rule) = low_rule else {
+        return (best_indices(&high), vec![]);
+    };
+    let low = (cards.iter())
+        .map(|a| {
+            (a.iter().combinations(Hand::SIZE))
+                .map(|a| LowValue::ace_to_five(&a.into_iter().copied().collect::<Vec<_>>()))
+                .filter(|a| low_rule == LowRule::AnyLow || a.qualifies(Rank::Eight))
+                .max()
+        })
+        .collect::<Vec<_>>();
+    let low = if low.iter().any(Option::is_some) {
+        best_indices(&low)
+    } else {
+        vec![]
+    };
+    (best_indices(&high), low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_qualifying_low_scoops() {
+        let hands = ["KS KD 9C 9H 2S", "QS QD QC 10H 10S"];
+        let result = split_winners(&hands, LowRule::EightOrBetter).unwrap();
+        assert_eq!(result.high, [hands[1]]);
+        assert!(result.low.is_empty());
+        assert_eq!(result.shares, [0.0, 1.0]);
+        // any low: the kings hold the better low, two pair against a full house
+        let result = split_winners(&hands, LowRule::AnyLow).unwrap();
+        assert_eq!(result.low, [hands[0]]);
+        assert_eq!(result.shares, [0.5, 0.5]);
+    }
+
+    #[test]
+    fn split_and_quartered() {
+        // draw hands: a flush for high against a seven low
+        let hands = ["2H 6H 9H JH KH", "AS 2D 4C 5S 7D"];
+        let result = split_winners(&hands, LowRule::EightOrBetter).unwrap();
+        assert_eq!((result.high, result.low), (vec![hands[0]], vec![hands[1]]));
+        // stud: the two lows tie, the second also wins high
+        let hands = [
+            "AS 2D 3C 4H 6S KD QC",
+            "AD 2S 3H 4C 6D 6H 6C",
+            "KS KH QS QH JD JC 10D",
+        ];
+        let result = split_winners(&hands, LowRule::EightOrBetter).unwrap();
+        assert_eq!(result.high, [hands[1]]);
+        assert_eq!(result.low, [hands[0], hands[1]]);
+        assert_eq!(result.shares, [0.25, 0.75, 0.0]);
+    }
+
+    #[test]
+    fn cards_are_checked_across_hands() {
+        assert_eq!(
+            split_winners(&["AS 2D 3C 4H 6S", "AS 7D 8C 9H JS"], LowRule::AnyLow),
+            Err(ParseError::DuplicateCard {
+                offset: 0,
+                card: "AS".to_string()
+            }
+            .in_hand(1))
+        );
+        assert!(split_winners(&["AS 2D 3C 4H"], LowRule::AnyLow).is_err());
+    }
+
+    #[test]
+    fn jokers_are_rejected() {
+        assert_eq!(
+            split_winners(
+                &["AD 2S 3H 4C 7D", "2D 3C JK 4H 6S"],
+                LowRule::EightOrBetter
+            ),
+            Err(ParseError::Joker { offset: 6 }.in_hand(1))
+        );
+    }
+}
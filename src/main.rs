@@ -0,0 +1,246 @@
+//! `poker` command-line tool: rank a hand, pick winners, compute equity.
+
+use poker::{
+    deck::Deck,
+    equity::{self, Equity, Settings},
+    try_winning_hands, Card, Hand, ParseError,
+};
+use std::{
+    fmt::Write,
+    io::{self, Read},
+    process::ExitCode,
+};
+
+const USAGE: &str = "\
+usage: poker [--json] <command> [arguments]
+
+commands:
+  rank \"<hand>\"                 category and description of a five-card hand
+  winners                       read one hand per line from stdin, print the winning lines
+  equity [options] \"<hole>\"...  Hold'em equity of two or more players' hole cards
+      --board \"<cards>\"         community cards dealt so far
+      --dead \"<cards>\"          cards known to be out of play
+      --samples <n>             Monte Carlo samples when enumeration is too large
+      --seed <n>                Monte Carlo seed
+
+cards are written rank then suit, e.g. \"AS KD 10H 2C 7S\", \"As Kd Th 2c 7s\" or \"A♠ K♦ T♥ 2♣ 7♠\"";
+
+enum CliError {
+    Usage(String),
+    Input(String),
+}
+
+impl From<ParseError> for CliError {
+    fn from(e: ParseError) -> Self {
+        CliError::Input(e.to_string())
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let json = args.iter().any(|a| a == "--json");
+    args.retain(|a| a != "--json");
+
+    let result = match args.first().map(String::as_str) {
+        Some("rank") => rank(&args[1..], json),
+        Some("winners") => winners(&args[1..], json),
+        Some("equity") => equity(&args[1..], json),
+        Some(a) => Err(CliError::Usage(format!("unknown command {a:?}"))),
+        None => Err(CliError::Usage("missing command".to_string())),
+    };
+    match result {
+        Ok(out) => {
+            println!("{out}");
+            ExitCode::SUCCESS
+        }
+        Err(CliError::Usage(e)) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+        Err(CliError::Input(e)) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn rank(args: &[String], json: bool) -> Result<String, CliError> {
+    let [hand] = args else {
+        return Err(CliError::Usage("rank takes exactly one hand".to_string()));
+    };
+    let value = hand.parse::<Hand>()?.evaluate();
+    if json {
+        let ranks = value.ranks().iter().map(|a| json_string(&a.to_string()));
+        Ok(format!(
+            "{{\"hand\":{},\"category\":{},\"description\":{},\"ranks\":[{}]}}",
+            json_string(hand),
+            json_string(&value.category().to_string()),
+            json_string(&value.describe()),
+            ranks.collect::<Vec<_>>().join(",")
+        ))
+    } else {
+        Ok(format!("{} ({})", value.describe(), value.category()))
+    }
+}
+
+fn winners(args: &[String], json: bool) -> Result<String, CliError> {
+    if !args.is_empty() {
+        return Err(CliError::Usage(
+            "winners reads hands from stdin".to_string(),
+        ));
+    }
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|e| CliError::Input(e.to_string()))?;
+    // blank lines are skipped, but errors report the line number in the input
+    let (lines, hands): (Vec<_>, Vec<_>) = input
+        .lines()
+        .enumerate()
+        .filter(|a| !a.1.trim().is_empty())
+        .map(|(i, a)| (i + 1, a.trim()))
+        .unzip();
+    if hands.is_empty() {
+        return Err(CliError::Input("no hands on stdin".to_string()));
+    }
+    let winners = try_winning_hands(&hands).map_err(|e| match e {
+        ParseError::InvalidHand { index, error } => {
+            CliError::Input(format!("line {}: {error}", lines[index]))
+        }
+        e => e.into(),
+    })?;
+    if json {
+        let winners = winners.iter().map(|a| json_string(a));
+        Ok(format!(
+            "{{\"winners\":[{}]}}",
+            winners.collect::<Vec<_>>().join(",")
+        ))
+    } else {
+        Ok(winners.join("\n"))
+    }
+}
+
+fn equity(args: &[String], json: bool) -> Result<String, CliError> {
+    let mut settings = Settings::default();
+    let mut board = vec![];
+    let mut dead = vec![];
+    let mut holes = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| CliError::Usage(format!("{arg} needs a value")))
+        };
+        match arg.as_str() {
+            "--board" => {
+                board = cards(value()?).map_err(|e| ParseError::InvalidBoard { error: e.into() })?
+            }
+            "--dead" => dead = cards(value()?)?,
+            "--samples" => {
+                settings.samples = number(value()?)?;
+                if settings.samples == 0 {
+                    return Err(CliError::Usage("--samples must be at least 1".to_string()));
+                }
+            }
+            "--seed" => settings.seed = number(value()?)?,
+            a if a.starts_with("--") => {
+                return Err(CliError::Usage(format!("unknown option {a:?}")))
+            }
+            a => holes.push(a),
+        }
+    }
+    if holes.len() < 2 {
+        return Err(CliError::Usage(
+            "equity needs at least two players".to_string(),
+        ));
+    }
+    let hole_cards = holes
+        .iter()
+        .enumerate()
+        .map(|(index, a)| {
+            cards(a).map_err(|e| ParseError::InvalidHand {
+                index,
+                error: e.into(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let result = equity::calculate(&hole_cards, &board, &dead, &settings)?;
+    Ok(if json {
+        equity_json(&holes, &result)
+    } else {
+        equity_text(&holes, &result)
+    })
+}
+
+fn equity_text(holes: &[&str], result: &Equity) -> String {
+    let mut out = String::new();
+    for (hole, a) in holes.iter().zip(&result.players) {
+        let _ = writeln!(
+            out,
+            "{hole:<8} equity {:6.2}%  win {:6.2}%  tie {:6.2}%",
+            a.equity * 100.0,
+            a.win * 100.0,
+            a.tie * 100.0
+        );
+    }
+    if result.exhaustive {
+        let _ = write!(out, "exhaustive over {} runouts", result.trials);
+    } else {
+        let _ = write!(
+            out,
+            "{} Monte Carlo samples, standard error up to {:.2}%",
+            result.trials,
+            result.max_std_error() * 100.0
+        );
+    }
+    out
+}
+
+fn equity_json(holes: &[&str], result: &Equity) -> String {
+    let players = holes.iter().zip(&result.players).map(|(hole, a)| {
+        format!(
+            "{{\"hole\":{},\"equity\":{},\"win\":{},\"tie\":{},\"loss\":{},\"std_error\":{}}}",
+            json_string(hole),
+            a.equity,
+            a.win,
+            a.tie,
+            a.loss,
+            a.std_error
+        )
+    });
+    format!(
+        "{{\"exhaustive\":{},\"trials\":{},\"players\":[{}]}}",
+        result.exhaustive,
+        result.trials,
+        players.collect::<Vec<_>>().join(",")
+    )
+}
+
+/// Space separated cards, parsed as a deck so repeated cards are rejected.
+fn cards(value: &str) -> Result<Vec<Card>, ParseError> {
+    Ok(value.parse::<Deck>()?.cards().to_vec())
+}
+
+fn number(value: &str) -> Result<u64, CliError> {
+    value
+        .parse()
+        .map_err(|_| CliError::Usage(format!("{value:?} is not a number")))
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
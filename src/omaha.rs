@@ -0,0 +1,198 @@
+//! Omaha and Omaha Hi-Lo: exactly two hole cards plus exactly three board cards.
+//!
+//! Hole cards may number four (PLO), five or six (5-card and 6-card PLO). There are no wild
+//! cards, so a joker is an error.
+
+use crate::{
+    best_indices,
+    card::{check_duplicates, check_no_jokers, parse_unseen, written_offset},
+    low::{HiLo, LowValue},
+    Card, Hand, ParseError,
+};
+use itertools::Itertools;
+
+/// Best high hand using exactly two of `hole` and exactly three of `board`.
+pub fn best_hand(hole: &[Card], board: &[Card]) -> Result<Hand, ParseError> {
+    check(hole, board)?;
+    Ok(best_high(hole, board))
+}
+
+/// Best 8-or-better low using exactly two of `hole` and exactly three of `board`,
+/// or `None` when no such combination qualifies.
+pub fn best_low(hole: &[Card], board: &[Card]) -> Result<Option<LowValue>, ParseError> {
+    check(hole, board)?;
+    Ok(best_eight_or_better(hole, board))
+}
+
+/// Given a shared board of 3 to 5 cards and each player's 4 to 6 hole cards,
+/// return the hole cards of the player(s) holding the best high hand.
+pub fn winning_hands<'a>(board: &str, holes: &[&'a str]) -> Result<Vec<&'a str>, ParseError> {
+    let (board, holes_cards) = parse(board, holes)?;
+    let values = holes_cards
+        .iter()
+        .map(|hole| best_high(hole, &board).evaluate())
+        .collect::<Vec<_>>();
+    Ok(best_indices(&values)
+        .into_iter()
+        .map(|i| holes[i])
+        .collect())
+}
+
+/// Omaha Hi-Lo: split the pot between the best high hand
+/// and the best 8-or-better low, each made from two hole cards and three board cards.
+///
+/// ```
+/// let result = poker::omaha::hi_lo_winners(
+///     "2H 5C 8D KS KD",
+///     &["AS 3D QH QC", "AD 3C KH 9C", "4S 6H JD JC"],
+/// )
+/// .unwrap();
+/// assert_eq!(result.high, ["AD 3C KH 9C"]);
+/// assert_eq!(result.low, ["AS 3D QH QC", "AD 3C KH 9C"]);
+/// assert_eq!(result.shares, [0.25, 0.75, 0.0]);
+/// ```
+pub fn hi_lo_winners<'a>(board: &str, holes: &[&'a str]) -> Result<HiLo<'a>, ParseError> {
+    let (board, holes_cards) = parse(board, holes)?;
+    let high = holes_cards
+        .iter()
+        .map(|hole| best_high(hole, &board).evaluate())
+        .collect::<Vec<_>>();
+    let low = holes_cards
+        .iter()
+        .map(|hole| best_eight_or_better(hole, &board))
+        .collect::<Vec<_>>();
+    let low = if low.iter().any(Option::is_some) {
+        best_indices(&low)
+    } else {
+        vec![]
+    };
+    Ok(HiLo::new(holes, &best_indices(&high), &low))
+}
+
+fn check(hole: &[Card], board: &[Card]) -> Result<(), ParseError> {
+    ParseError::check_count(4..=6, hole.len())?;
+    ParseError::check_count(3..=5, board.len())?;
+    let cards = [hole, board].concat();
+    if let Some(i) = cards.iter().position(Card::is_joker) {
+        return Err(ParseError::Joker {
+            offset: written_offset(&cards, i),
+        });
+    }
+    check_duplicates(&cards)
+}
+
+fn parse(board: &str, holes: &[&str]) -> Result<(Vec<Card>, Vec<Vec<Card>>), ParseError> {
+    let board = parse_unseen(board, 3..=5, &[])
+        .and_then(|cards| check_no_jokers(board, &cards).map(|_| cards))
+        .map_err(ParseError::in_board)?;
+    let mut seen = board.clone();
+    let mut holes_cards = Vec::with_capacity(holes.len());
+    for (i, hole) in holes.iter().enumerate() {
+        let cards = parse_unseen(hole, 4..=6, &seen)
+            .and_then(|cards| check_no_jokers(hole, &cards).map(|_| cards))
+            .map_err(|e| e.in_hand(i))?;
+        seen.extend(&cards);
+        holes_cards.push(cards);
+    }
+    Ok((board, holes_cards))
+}
+
+/// Every legal five-card selection: two from the hole, three from the board.
+fn selections<'a>(hole: &'a [Card], board: &'a [Card]) -> impl Iterator<Item = Vec<Card>> + 'a {
+    hole.iter()
+        .copied()
+        .combinations(2)
+        .cartesian_product(board.iter().copied().combinations(3).collect::<Vec<_>>())
+        .map(|(a, b)| [a, b].concat())
+}
+
+fn best_high(hole: &[Card], board: &[Card]) -> Hand {
+    selections(hole, board)
+        .map(Hand::from_cards)
+        .max_by_key(Hand::evaluate)
+        .expect("at least two hole cards and three board cards")
+}
+
+fn best_eight_or_better(hole: &[Card], board: &[Card]) -> Option<LowValue> {
+    selections(hole, board)
+        .filter_map(|a| LowValue::eight_or_better(&a))
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{card::parse_cards, Hands, Rank};
+
+    fn cards(value: &str) -> Vec<Card> {
+        parse_cards(value).unwrap()
+    }
+
+    #[test]
+    fn exactly_two_hole_cards() {
+        // four spades on board and one in the hole is no flush in Omaha
+        let hand = best_hand(&cards("AS KD QD JD"), &cards("2S 5S 8S 9S KC")).unwrap();
+        assert_eq!(hand.evaluate().category(), Hands::OnePair);
+
+        // four of a kind in the hole only plays two of them
+        let hand = best_hand(&cards("9C 9D 9H 9S"), &cards("2S 5D 8H JC KC")).unwrap();
+        assert_eq!(hand.evaluate().category(), Hands::OnePair);
+    }
+
+    #[test]
+    fn five_and_six_card_plo() {
+        let hand = best_hand(&cards("AS KS 2C 3C 7D"), &cards("QS JS 10S 4H 4D")).unwrap();
+        assert_eq!(hand.evaluate().category(), Hands::StraightFlush);
+        let hand = best_hand(&cards("AS KS 2C 3C 7D 7H"), &cards("QS JS 7C 4H 4D")).unwrap();
+        assert_eq!(hand.evaluate().category(), Hands::FullHouse);
+        assert!(best_hand(&cards("AS KS 2C"), &cards("QS JS 7C")).is_err());
+    }
+
+    #[test]
+    fn low_needs_three_low_board_cards() {
+        assert_eq!(
+            best_low(&cards("AS 2D 3H 4C"), &cards("5S 9D 10H JC QD")).unwrap(),
+            None
+        );
+        let low = best_low(&cards("AS 2D KH KC"), &cards("3S 4D 8H JC QD"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            low.ranks(),
+            [Rank::Eight, Rank::Four, Rank::Three, Rank::Two, Rank::Ace]
+        );
+    }
+
+    #[test]
+    fn high_scoops_without_qualifying_low() {
+        let result = hi_lo_winners("9H 10C JD KS KD", &["AS 2D QH 3C", "4D 5C 6H 7S"]).unwrap();
+        assert_eq!(result.high, ["AS 2D QH 3C"]);
+        assert!(result.low.is_empty());
+        assert_eq!(result.shares, [1.0, 0.0]);
+    }
+
+    #[test]
+    fn split_high_and_low() {
+        let result = hi_lo_winners("2H 4C 7D KS KD", &["AS 3D 5H 6C", "KH QC JS 10S"]).unwrap();
+        assert_eq!(result.high, ["KH QC JS 10S"]);
+        assert_eq!(result.low, ["AS 3D 5H 6C"]);
+        assert_eq!(result.shares, [0.5, 0.5]);
+    }
+
+    #[test]
+    fn jokers_are_rejected() {
+        assert_eq!(
+            hi_lo_winners("2H 5C 8D KS KD", &["AS 4D QH QC", "JK 3D QD JC"]),
+            Err(ParseError::Joker { offset: 0 }.in_hand(1))
+        );
+        assert_eq!(
+            hi_lo_winners("2H 5C JK KS KD", &["AS 4D QH QC"]),
+            Err(ParseError::Joker { offset: 6 }.in_board())
+        );
+        let hole = cards("JK 3D QH QC");
+        assert_eq!(
+            best_low(&hole, &cards("2H 5C 8D KS KD")),
+            Err(ParseError::Joker { offset: 0 })
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! Hold'em outs: the unseen cards that improve a hand, and exactly how likely they are to come.
+//!
+//! On the flop the chance of improving by the river is enumerated over every turn and river,
+//! so runner-runner draws count and nothing rests on the rule of 4 and 2.
+
+use crate::{deck::Deck, equity, holdem::best_of, Card, HandValue, Hands, ParseError};
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+/// Result of [`outs`] and [`outs_against`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outs {
+    /// What the hand makes now.
+    pub current: HandValue,
+    /// Every out, grouped by the category it makes, best category first.
+    pub groups: Vec<(Hands, Vec<Card>)>,
+    /// Cards that could come next: the deck less the hole cards, board and dead cards.
+    pub unseen: usize,
+    /// Chance of hitting on the turn; `None` when the turn is already out.
+    pub turn: Option<f64>,
+    /// Chance of having hit once the river is out.
+    pub river: f64,
+}
+
+impl Outs {
+    /// Number of outs.
+    pub fn count(&self) -> usize {
+        self.groups.iter().map(|a| a.1.len()).sum()
+    }
+
+    /// Every out, best category first.
+    pub fn cards(&self) -> impl Iterator<Item = Card> + '_ {
+        self.groups.iter().flat_map(|a| a.1.iter().copied())
+    }
+}
+
+/// Cards that improve two `hole` cards on a 3 or 4 card `board` to a better [`Hands`]
+/// category, leaving out `dead` cards known to be out of play.
+///
+/// ```
+/// use poker::{outs::outs, Hands};
+///
+/// let cards = |a: &str| a.split(' ').map(|a| a.parse().unwrap()).collect::<Vec<_>>();
+/// let outs = outs(&cards("AH KH"), &cards("QH 7H 2C 2D"), &[]).unwrap();
+/// // nine hearts for the flush, then any ace, king, queen or seven for two pair
+/// // and the deuce of spades for trips
+/// assert_eq!(outs.groups[0], (Hands::Flush, cards("2H 3H 4H 5H 6H 8H 9H 10H JH")));
+/// assert_eq!(outs.count(), 22);
+/// assert_eq!(outs.river, 22.0 / 46.0);
+/// ```
+pub fn outs(hole: &[Card], board: &[Card], dead: &[Card]) -> Result<Outs, ParseError> {
+    let unseen = unseen(&[hole.to_vec()], board, dead)?;
+    let current = best_of(&[hole, board].concat()).evaluate();
+    let category = current.category();
+    let improves =
+        |cards: &[Card]| best_of(&[hole, cards].concat()).evaluate().category() > category;
+    Ok(find(hole, board, unseen, current, improves))
+}
+
+/// Cards after which two `hole` cards beat the `opponent`'s two, on a 3 or 4 card `board`
+/// and leaving out `dead` cards; a tie is not a win. The outs are grouped by the category
+/// the hand makes with them.
+///
+/// Errors in `hole` are reported as [`ParseError::InvalidHand`] with index 0, and errors in
+/// `opponent` with index 1.
+///
+/// ```
+/// use poker::outs::outs_against;
+///
+/// let cards = |a: &str| a.split(' ').map(|a| a.parse().unwrap()).collect::<Vec<_>>();
+/// // aces against a set of kings need one of the two other aces
+/// let outs = outs_against(&cards("AS AD"), &cards("KH KC"), &cards("KD 7S 2C"), &[]).unwrap();
+/// assert_eq!(outs.cards().collect::<Vec<_>>(), cards("AC AH"));
+/// assert_eq!(outs.turn, Some(2.0 / 45.0));
+/// ```
+pub fn outs_against(
+    hole: &[Card],
+    opponent: &[Card],
+    board: &[Card],
+    dead: &[Card],
+) -> Result<Outs, ParseError> {
+    let unseen = unseen(&[hole.to_vec(), opponent.to_vec()], board, dead)?;
+    let current = best_of(&[hole, board].concat()).evaluate();
+    let wins = |cards: &[Card]| {
+        best_of(&[hole, cards].concat()).evaluate()
+            > best_of(&[opponent, cards].concat()).evaluate()
+    };
+    Ok(find(hole, board, unseen, current, wins))
+}
+
+/// Validate the cards and return the deck without them, in deck order.
+fn unseen(holes: &[Vec<Card>], board: &[Card], dead: &[Card]) -> Result<Vec<Card>, ParseError> {
+    ParseError::check_count(3..=4, board.len()).map_err(ParseError::in_board)?;
+    let known = equity::validate(holes, board, dead)?;
+    let mut deck = Deck::new();
+    deck.remove(&known).expect("validated distinct cards");
+    Ok(deck.cards().to_vec())
+}
+
+/// Collect the cards for which `hits` holds on the next street, and the chance it holds
+/// after the river. `hits` is given the whole board.
+fn find(
+    hole: &[Card],
+    board: &[Card],
+    unseen: Vec<Card>,
+    current: HandValue,
+    hits: impl Fn(&[Card]) -> bool,
+) -> Outs {
+    let mut groups = BTreeMap::<Hands, Vec<Card>>::new();
+    for &card in &unseen {
+        let cards = [board, &[card]].concat();
+        if hits(&cards) {
+            let category = best_of(&[hole, &cards].concat()).evaluate().category();
+            groups.entry(category).or_default().push(card);
+        }
+    }
+    let count = groups.values().map(Vec::len).sum::<usize>();
+    let next = count as f64 / unseen.len() as f64;
+    let (turn, river) = match board.len() {
+        3 => {
+            let runouts = unseen
+                .iter()
+                .tuple_combinations()
+                .map(|(&turn, &river)| hits(&[board, &[turn, river]].concat()))
+                .collect::<Vec<_>>();
+            let hit = runouts.iter().filter(|&&a| a).count();
+            (Some(next), hit as f64 / runouts.len() as f64)
+        }
+        _ => (None, next),
+    };
+    Outs {
+        current,
+        groups: groups.into_iter().rev().collect(),
+        unseen: unseen.len(),
+        turn,
+        river,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{card::parse_cards, equity::Settings};
+
+    fn cards(value: &str) -> Vec<Card> {
+        parse_cards(value).unwrap()
+    }
+
+    #[test]
+    fn flush_draw_on_the_flop() {
+        let outs = outs(&cards("AH KH"), &cards("QH 7H 2C"), &[]).unwrap();
+        assert_eq!(outs.current.category(), Hands::HighCard);
+        // nine hearts, and the fourteen other cards pairing a hole or board card
+        let counts = outs
+            .groups
+            .iter()
+            .map(|a| (a.0, a.1.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(counts, [(Hands::Flush, 9), (Hands::OnePair, 14)]);
+        assert_eq!(outs.unseen, 47);
+        assert_eq!(outs.turn, Some(23.0 / 47.0));
+        // only two blanks in a row miss: neither a heart nor pairing, nor pairing each other,
+        // nor running out a straight
+        let blanks =
+            cards("3C 3D 3S 4C 4D 4S 5C 5D 5S 6C 6D 6S 8C 8D 8S 9C 9D 9S 10C 10D 10S JC JD JS");
+        let misses = blanks
+            .iter()
+            .tuple_combinations()
+            .filter(|(a, b)| a.rank() != b.rank())
+            .filter(|(a, b)| {
+                let board = [cards("QH 7H 2C"), vec![**a, **b]].concat();
+                best_of(&[cards("AH KH"), board].concat())
+                    .evaluate()
+                    .category()
+                    == Hands::HighCard
+            })
+            .count();
+        assert_eq!(outs.river, (1081 - misses) as f64 / 1081.0);
+    }
+
+    #[test]
+    fn made_hands_and_dead_cards() {
+        // a made straight only improves with a spade, pairing the board is no better
+        let outs = outs(&cards("9S 8S"), &cards("7S 6D 5S 2C"), &cards("AS KS")).unwrap();
+        assert_eq!(outs.current.category(), Hands::Straight);
+        assert_eq!(
+            outs.groups,
+            [
+                (Hands::StraightFlush, cards("6S")),
+                (Hands::Flush, cards("2S 3S 4S 10S JS QS"))
+            ]
+        );
+        assert_eq!(outs.unseen, 44);
+        assert_eq!(outs.turn, None);
+        assert_eq!(outs.river, 7.0 / 44.0);
+    }
+
+    #[test]
+    fn against_an_opponent_matches_equity() {
+        let (hole, opponent, board) = (cards("AS AD"), cards("KH KC"), cards("KD 7S 2C"));
+        let outs = outs_against(&hole, &opponent, &board, &[]).unwrap();
+        let equity =
+            equity::calculate(&[hole, opponent], &board, &[], &Settings::default()).unwrap();
+        assert!((outs.river - equity.players[0].win).abs() < 1e-12);
+        assert_eq!(outs.groups, [(Hands::ThreeOfAKind, cards("AC AH"))]);
+    }
+
+    #[test]
+    fn errors() {
+        let hole = cards("AS AD");
+        assert_eq!(
+            outs(&hole, &cards("KD 7S"), &[]),
+            Err(ParseError::InvalidBoard {
+                error: Box::new(ParseError::WrongCardCount {
+                    expected: 3..=4,
+                    found: 2
+                })
+            })
+        );
+        assert!(outs(&hole, &cards("KD 7S 2C 3C 4C"), &[]).is_err());
+        assert!(outs_against(&hole, &cards("AS KC"), &cards("KD 7S 2C"), &[]).is_err());
+        assert!(outs(&hole, &cards("KD 7S 2C"), &cards("KD")).is_err());
+    }
+}
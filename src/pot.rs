@@ -0,0 +1,259 @@
+//! Main pot and side pots, and how they are paid out at showdown.
+//!
+//! ```
+//! use poker::pot::{self, OddChip};
+//!
+//! // seat 0 is all-in for 50, seats 1 and 2 go on to put in 200 each
+//! let pots = pot::build(&[50, 200, 200], &[false, false, false]);
+//! assert_eq!(pots.len(), 2);
+//! assert_eq!((pots[0].amount, &pots[0].eligible[..]), (150, &[0, 1, 2][..]));
+//! assert_eq!((pots[1].amount, &pots[1].eligible[..]), (300, &[1, 2][..]));
+//!
+//! // seat 0 has the best hand, seats 1 and 2 tie for second
+//! let payouts = pot::award(&pots, &[3, 2, 2], OddChip::LeftOfButton(0)).unwrap();
+//! assert_eq!(payouts, [150, 150, 150]);
+//! ```
+
+use crate::{best_indices, Card, PotError, Suit};
+
+/// Chips that only the `eligible` players can win.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pot {
+    pub amount: u64,
+    /// Seats of the live players who paid into this pot, in seat order.
+    pub eligible: Vec<usize>,
+}
+
+/// Who gets the chips left over when a pot does not split evenly.
+#[derive(Debug, Clone, Copy)]
+pub enum OddChip<'a> {
+    /// The winners closest to the left of the button, given as a seat.
+    LeftOfButton(usize),
+    /// The winners holding the highest card, by rank and then by suit in bridge order.
+    ///
+    /// Holds every player's cards, by seat.
+    HighestCard(&'a [Vec<Card>]),
+}
+
+/// Split the chips each seat put in into a main pot and side pots, smallest all-in first.
+///
+/// A new pot starts at every contribution a live player cannot match; folded players' chips
+/// go into the pots but they are not eligible for any. Chips nobody live matched, like an
+/// uncalled bet, form a last pot for whoever put them in.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length.
+pub fn build(contributions: &[u64], folded: &[bool]) -> Vec<Pot> {
+    assert_eq!(contributions.len(), folded.len());
+    let live = |i: usize| !folded[i];
+    let live_max = (0..contributions.len())
+        .filter(|&i| live(i))
+        .map(|i| contributions[i])
+        .max()
+        .unwrap_or_default();
+    let mut levels = contributions.to_vec();
+    levels.sort_unstable();
+    levels.dedup();
+    let mut pots = Vec::<Pot>::new();
+    let mut previous = 0;
+    for level in levels.into_iter().filter(|&a| a > 0) {
+        let amount = (contributions.iter())
+            .map(|a| a.min(&level) - a.min(&previous))
+            .sum();
+        previous = level;
+        let eligible = (0..contributions.len())
+            .filter(|&i| live(i) && contributions[i] >= level.min(live_max))
+            .collect::<Vec<_>>();
+        match pots.last_mut() {
+            Some(pot) if pot.eligible == eligible => pot.amount += amount,
+            _ => pots.push(Pot { amount, eligible }),
+        }
+    }
+    pots
+}
+
+/// Award each pot to its best eligible hands, ties splitting it, and return each seat's winnings.
+///
+/// `values` holds every seat's hand value, e.g. a [`crate::HandValue`]; values of seats
+/// that are not eligible for a pot are ignored for it. A pot nobody is eligible for is an
+/// error, as its chips would have no owner.
+pub fn award<T: Ord>(pots: &[Pot], values: &[T], odd_chip: OddChip) -> Result<Vec<u64>, PotError> {
+    let mut payouts = vec![0; values.len()];
+    for (n, pot) in pots.iter().enumerate() {
+        if pot.eligible.is_empty() {
+            return Err(PotError::NobodyEligible { pot: n });
+        }
+        let eligible = pot.eligible.iter().map(|&i| &values[i]).collect::<Vec<_>>();
+        let mut winners = (best_indices(&eligible).into_iter())
+            .map(|i| pot.eligible[i])
+            .collect::<Vec<_>>();
+        match odd_chip {
+            OddChip::LeftOfButton(button) => {
+                let n = values.len();
+                winners.sort_by_key(|&i| (i + n - button - 1) % n);
+            }
+            OddChip::HighestCard(cards) => {
+                winners.sort_by_key(|&i| std::cmp::Reverse(highest_card(&cards[i])));
+            }
+        }
+        let count = winners.len() as u64;
+        for (k, &i) in winners.iter().enumerate() {
+            payouts[i] += pot.amount / count + u64::from((k as u64) < pot.amount % count);
+        }
+    }
+    Ok(payouts)
+}
+
+/// [`build`] the pots and [`award`] them; every player having folded is an error.
+pub fn distribute<T: Ord>(
+    contributions: &[u64],
+    folded: &[bool],
+    values: &[T],
+    odd_chip: OddChip,
+) -> Result<Vec<u64>, PotError> {
+    award(&build(contributions, folded), values, odd_chip)
+}
+
+/// Highest real card by rank and then suit; jokers do not count.
+fn highest_card(cards: &[Card]) -> Option<(u8, Suit)> {
+    (cards.iter())
+        .filter(|a| !a.is_joker())
+        .map(|a| (a.rank().value(), a.suit()))
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiple_all_ins() {
+        let contributions = [50, 100, 200, 200];
+        let pots = build(&contributions, &[false; 4]);
+        assert_eq!(
+            pots,
+            [
+                Pot {
+                    amount: 200,
+                    eligible: vec![0, 1, 2, 3]
+                },
+                Pot {
+                    amount: 150,
+                    eligible: vec![1, 2, 3]
+                },
+                Pot {
+                    amount: 200,
+                    eligible: vec![2, 3]
+                },
+            ]
+        );
+        let payouts = award(&pots, &[4, 3, 1, 1], OddChip::LeftOfButton(0)).unwrap();
+        assert_eq!(payouts, [200, 150, 100, 100]);
+        // the short stack loses, the rest is decided between the others
+        let payouts = award(&pots, &[0, 3, 5, 1], OddChip::LeftOfButton(0)).unwrap();
+        assert_eq!(payouts, [0, 0, 550, 0]);
+    }
+
+    #[test]
+    fn folded_chips_stay_in_the_pot() {
+        let pots = build(&[30, 20, 100, 100], &[true, false, false, false]);
+        assert_eq!(
+            pots,
+            [
+                Pot {
+                    amount: 80,
+                    eligible: vec![1, 2, 3]
+                },
+                Pot {
+                    amount: 170,
+                    eligible: vec![2, 3]
+                },
+            ]
+        );
+        // a folded player's value is never looked at
+        let payouts = award(&pots, &[9, 1, 0, 0], OddChip::LeftOfButton(0)).unwrap();
+        assert_eq!(payouts, [0, 80, 85, 85]);
+    }
+
+    #[test]
+    fn uncalled_bet_returns() {
+        let pots = build(&[10, 50, 0], &[false, false, true]);
+        assert_eq!(pots[1].amount, 40);
+        assert_eq!(pots[1].eligible, [1]);
+        assert_eq!(
+            award(&pots, &[1, 0, 0], OddChip::LeftOfButton(0)),
+            Ok(vec![20, 40, 0])
+        );
+    }
+
+    #[test]
+    fn odd_chips_left_of_button() {
+        let contributions = [10, 10, 10, 1];
+        let folded = [false, false, false, true];
+        let payouts =
+            distribute(&contributions, &folded, &[1; 4], OddChip::LeftOfButton(3)).unwrap();
+        assert_eq!(payouts, [11, 10, 10, 0]);
+        let payouts =
+            distribute(&contributions, &folded, &[1; 4], OddChip::LeftOfButton(0)).unwrap();
+        assert_eq!(payouts, [10, 11, 10, 0]);
+        let payouts =
+            distribute(&[11, 11, 11, 0], &folded, &[1; 4], OddChip::LeftOfButton(0)).unwrap();
+        assert_eq!(payouts, [11, 11, 11, 0]);
+        let payouts =
+            distribute(&[11, 11, 11, 2], &folded, &[1; 4], OddChip::LeftOfButton(1)).unwrap();
+        assert_eq!(payouts, [12, 11, 12, 0]);
+    }
+
+    #[test]
+    fn odd_chip_to_highest_suit() {
+        let cards = ["AH KD QC JS 10H", "AS KC QD JH 10C", "2C 3C"].map(|a| {
+            a.split(' ')
+                .map(|a| a.parse().unwrap())
+                .collect::<Vec<Card>>()
+        });
+        // a folded chip makes the pot of 7 odd
+        let contributions = [3, 3, 1];
+        let folded = [false, false, true];
+        let payouts = distribute(
+            &contributions,
+            &folded,
+            &[1, 1, 0],
+            OddChip::HighestCard(&cards),
+        )
+        .unwrap();
+        assert_eq!(payouts, [3, 4, 0]);
+        let payouts = distribute(
+            &contributions,
+            &folded,
+            &[1, 1, 0],
+            OddChip::LeftOfButton(1),
+        )
+        .unwrap();
+        assert_eq!(payouts, [4, 3, 0]);
+    }
+
+    #[test]
+    fn chips_are_conserved() {
+        // every mix of contributions, folds and values on three seats
+        for code in 0..(4 * 4 * 4 * 8 * 27) {
+            let contributions = [code % 4, code / 4 % 4, code / 16 % 4].map(|a| a * 7 + 3);
+            let folded = [0, 1, 2].map(|i| (code / 64 % 8) >> i & 1 == 1);
+            let values = [0, 1, 2].map(|i| code / 512 / 3u64.pow(i) % 3);
+            let payouts = distribute(&contributions, &folded, &values, OddChip::LeftOfButton(0));
+            if folded.iter().all(|&a| a) {
+                assert_eq!(payouts, Err(PotError::NobodyEligible { pot: 0 }));
+                continue;
+            }
+            let payouts = payouts.unwrap();
+            assert_eq!(
+                payouts.iter().sum::<u64>(),
+                contributions.iter().sum::<u64>(),
+                "{contributions:?} {folded:?} {values:?}"
+            );
+            for i in 0..3 {
+                assert!(!folded[i] || payouts[i] == 0);
+            }
+        }
+    }
+}
@@ -0,0 +1,292 @@
+//! Hand ranges in the usual notation, e.g. `QQ+, AKs, A5s-A2s, KQo, 22-66, AKo:0.5`.
+
+use crate::{Card, ParseError, Rank, Suit};
+use itertools::Itertools;
+use std::str::FromStr;
+
+/// Two specific hole cards and how often they are played, from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Combo {
+    pub cards: [Card; 2],
+    pub weight: f64,
+}
+
+impl Combo {
+    fn shares_card_with(&self, cards: &[Card]) -> bool {
+        self.cards
+            .iter()
+            .any(|a| cards.iter().any(|b| a.is_same(b)))
+    }
+}
+
+/// A set of weighted hole card combinations.
+///
+/// Comma separated entries, each optionally followed by `:weight` (default 1):
+///
+/// | entry     | meaning                                     |
+/// |-----------|---------------------------------------------|
+/// | `QQ`      | a pair, 6 combos                            |
+/// | `AKs`     | suited, 4 combos                            |
+/// | `AKo`     | offsuit, 12 combos                          |
+/// | `AK`      | suited or offsuit, 16 combos                |
+/// | `QQ+`     | QQ, KK and AA                               |
+/// | `A9s+`    | A9s, ATs, AJs, AQs and AKs                  |
+/// | `22-66`   | every pair from 22 to 66                    |
+/// | `A2s-A5s` | A2s, A3s, A4s and A5s                       |
+/// | `AhKh`    | exactly those two cards                     |
+///
+/// Ranks are `A K Q J T 9 … 2` and suits `c d h s`.
+/// When entries overlap, the later one sets the weight.
+///
+/// ```
+/// use poker::range::Range;
+///
+/// let range: Range = "QQ+, AKs, A5s-A2s, KQo, 22-66".parse().unwrap();
+/// assert_eq!(range.len(), 3 * 6 + 4 + 4 * 4 + 12 + 5 * 6);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Range {
+    combos: Vec<Combo>,
+}
+
+impl Range {
+    pub fn combos(&self) -> &[Combo] {
+        &self.combos
+    }
+
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    /// The combos that share no card with `dead`, e.g. the board.
+    pub fn without(&self, dead: &[Card]) -> Range {
+        Range {
+            combos: self
+                .combos
+                .iter()
+                .filter(|a| !a.shares_card_with(dead))
+                .copied()
+                .collect(),
+        }
+    }
+
+    fn insert(&mut self, cards: [Card; 2], weight: f64) {
+        let same = |a: &&mut Combo| a.cards.iter().all(|b| cards.iter().any(|c| b.is_same(c)));
+        match self.combos.iter_mut().find(same) {
+            Some(a) => a.weight = weight,
+            None => self.combos.push(Combo { cards, weight }),
+        }
+    }
+}
+
+impl FromStr for Range {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut range = Range::default();
+        let mut offset = 0;
+        for entry in value.split(',') {
+            let trimmed = entry.trim();
+            let start = offset + entry.len() - entry.trim_start().len();
+            let (combos, weight) = parse_entry(trimmed).ok_or_else(|| ParseError::BadRange {
+                offset: start,
+                entry: trimmed.to_string(),
+            })?;
+            for cards in combos {
+                range.insert(cards, weight);
+            }
+            offset += entry.len() + 1;
+        }
+        Ok(range)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Suitedness {
+    Pair,
+    Suited,
+    Offsuit,
+    Any,
+}
+
+fn parse_entry(entry: &str) -> Option<(Vec<[Card; 2]>, f64)> {
+    let (body, weight) = match entry.split_once(':') {
+        Some((body, weight)) => (body, weight.trim().parse::<f64>().ok()?),
+        None => (entry, 1.0),
+    };
+    if !(0.0..=1.0).contains(&weight) {
+        return None;
+    }
+    let body = body.trim();
+
+    let classes = if let Some((a, b)) = body.split_once('-') {
+        let (a, b) = (parse_class(a)?, parse_class(b)?);
+        if a.2 != b.2 {
+            return None;
+        }
+        if a.2 == Suitedness::Pair {
+            ranks_between(a.0, b.0).map(|r| (r, r, a.2)).collect()
+        } else if a.0 == b.0 {
+            ranks_between(a.1, b.1).map(|k| (a.0, k, a.2)).collect()
+        } else {
+            return None;
+        }
+    } else if let Some(body) = body.strip_suffix('+') {
+        let (high, low, suitedness) = parse_class(body)?;
+        if suitedness == Suitedness::Pair {
+            ranks_between(high, Rank::Ace)
+                .map(|r| (r, r, suitedness))
+                .collect()
+        } else {
+            ranks_between(low, high)
+                .filter(|&k| k != high)
+                .map(|k| (high, k, suitedness))
+                .collect()
+        }
+    } else if let Some(cards) = parse_combo(body) {
+        return Some((vec![cards], weight));
+    } else {
+        vec![parse_class(body)?]
+    };
+
+    let combos = classes
+        .into_iter()
+        .flat_map(|(high, low, suitedness)| class_combos(high, low, suitedness))
+        .collect();
+    Some((combos, weight))
+}
+
+/// `AK`, `AKs`, `AKo` or `AA`, as (high rank, low rank, suitedness).
+fn parse_class(value: &str) -> Option<(Rank, Rank, Suitedness)> {
+    let chars = value.trim().chars().collect::<Vec<_>>();
+    let (a, b) = (rank(*chars.first()?)?, rank(*chars.get(1)?)?);
+    let (high, low) = (a.max(b), a.min(b));
+    let suitedness = match (chars.get(2), chars.len()) {
+        (None, 2) if high == low => Suitedness::Pair,
+        (None, 2) => Suitedness::Any,
+        (Some('s'), 3) if high != low => Suitedness::Suited,
+        (Some('o'), 3) if high != low => Suitedness::Offsuit,
+        _ => return None,
+    };
+    Some((high, low, suitedness))
+}
+
+/// Exactly two cards, e.g. `AhKh`.
+fn parse_combo(value: &str) -> Option<[Card; 2]> {
+    let chars = value.chars().collect::<Vec<_>>();
+    if chars.len() != 4 {
+        return None;
+    }
+    let a = Card::new(rank(chars[0])?, suit(chars[1])?);
+    let b = Card::new(rank(chars[2])?, suit(chars[3])?);
+    (!a.is_same(&b)).then_some([a, b])
+}
+
+fn rank(c: char) -> Option<Rank> {
+    match c {
+        'A' => Some(Rank::Ace),
+        'K' => Some(Rank::King),
+        'Q' => Some(Rank::Queen),
+        'J' => Some(Rank::Jack),
+        'T' => Some(Rank::Ten),
+        '2'..='9' => Rank::from_value(c.to_digit(10)? as u8),
+        _ => None,
+    }
+}
+
+fn suit(c: char) -> Option<Suit> {
+    match c {
+        'c' => Some(Suit::Club),
+        'd' => Some(Suit::Diamond),
+        'h' => Some(Suit::Heart),
+        's' => Some(Suit::Spade),
+        _ => None,
+    }
+}
+
+fn ranks_between(a: Rank, b: Rank) -> impl Iterator<Item = Rank> {
+    let (low, high) = (a.min(b), a.max(b));
+    Rank::ALL
+        .into_iter()
+        .filter(move |&r| low <= r && r <= high)
+}
+
+fn class_combos(high: Rank, low: Rank, suitedness: Suitedness) -> Vec<[Card; 2]> {
+    let pairs = Suit::ALL.into_iter().cartesian_product(Suit::ALL);
+    let suits = match suitedness {
+        Suitedness::Pair => pairs.filter(|(a, b)| a < b).collect::<Vec<_>>(),
+        Suitedness::Suited => pairs.filter(|(a, b)| a == b).collect(),
+        Suitedness::Offsuit => pairs.filter(|(a, b)| a != b).collect(),
+        Suitedness::Any => pairs.collect(),
+    };
+    suits
+        .into_iter()
+        .map(|(a, b)| [Card::new(high, a), Card::new(low, b)])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::parse_cards;
+
+    fn len(value: &str) -> usize {
+        value.parse::<Range>().unwrap().len()
+    }
+
+    #[test]
+    fn combo_counts() {
+        assert_eq!(len("AA"), 6);
+        assert_eq!(len("AKs"), 4);
+        assert_eq!(len("AKo"), 12);
+        assert_eq!(len("AK"), 16);
+        assert_eq!(len("QQ+"), 18);
+        assert_eq!(len("A9s+"), 20);
+        assert_eq!(len("K9o+"), 48);
+        assert_eq!(len("22-66"), 30);
+        assert_eq!(len("66-22"), 30);
+        assert_eq!(len("A5s-A2s"), 16);
+        assert_eq!(len("AhKh"), 1);
+        assert_eq!(len("AK, AKs"), 16);
+    }
+
+    #[test]
+    fn weights() {
+        let range: Range = "AKs:0.5, AhKh".parse().unwrap();
+        assert_eq!(range.len(), 4);
+        assert_eq!(range.combos().iter().filter(|a| a.weight == 0.5).count(), 3);
+    }
+
+    #[test]
+    fn card_removal() {
+        let range: Range = "AA, AKs".parse().unwrap();
+        let board = parse_cards("AH 7D 2C").unwrap();
+        // three aces left make three pairs, and three suited kings
+        assert_eq!(range.without(&board).len(), 6);
+    }
+
+    #[test]
+    fn malformed_entries() {
+        for (value, offset, entry) in [
+            ("QQ+, AKx", 5, "AKx"),
+            ("QQ, AAs", 4, "AAs"),
+            ("22-AKs", 0, "22-AKs"),
+            ("A5s-K2s", 0, "A5s-K2s"),
+            ("AKs:2", 0, "AKs:2"),
+            ("AA,,KK", 3, ""),
+        ] {
+            assert_eq!(
+                value.parse::<Range>(),
+                Err(ParseError::BadRange {
+                    offset,
+                    entry: entry.to_string()
+                }),
+                "{value}"
+            );
+        }
+    }
+}
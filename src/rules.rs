@@ -0,0 +1,327 @@
+//! Ranking rules, so the same cards can be compared as high or as low hands.
+
+use crate::{
+    best_indices,
+    card::{parse_cards, written_offset},
+    low::LowValue,
+    Card, Hand, HandValue, Hands, ParseError, Rank,
+};
+use itertools::Itertools;
+use std::cmp::Reverse;
+
+/// How hands are ranked against each other.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Rules {
+    /// Ordinary high hands, as in [`crate::winning_hands`].
+    High,
+    /// Ace-to-five lowball: aces are low, straights and flushes are ignored.
+    /// The best hand is 5-4-3-2-A.
+    AceToFive,
+    /// Deuce-to-seven lowball: aces are high, straights and flushes count against you.
+    /// The best hand is 7-5-4-3-2 in at least two suits.
+    DeuceToSeven,
+    /// Razz: ace-to-five lowball, playing the best five of up to seven cards.
+    Razz,
+    /// Short deck (6+) Hold'em, dealt from [`Deck::short`](crate::deck::Deck::short):
+    /// A-6-7-8-9 is the lowest straight and a flush beats a full house.
+    /// The best five of up to seven cards play, and cards below six and jokers are rejected.
+    ShortDeck,
+    /// [`Rules::ShortDeck`] where three of a kind also beats a straight.
+    ShortDeckTripsBeatStraight,
+}
+
+/// Strength of a hand under some [`Rules`]; the greater strength wins.
+///
+/// Only strengths produced by the same rules are meaningful to compare.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Strength {
+    High(HandValue),
+    AceToFive(LowValue),
+    DeuceToSeven(Reverse<HandValue>),
+    /// The category's place in the short deck order, then the value itself.
+    ShortDeck(u8, HandValue),
+}
+
+impl Rules {
+    /// Number of cards a hand may hold under these rules.
+    fn hand_sizes(self) -> std::ops::RangeInclusive<usize> {
+        match self {
+            Rules::Razz | Rules::ShortDeck | Rules::ShortDeckTripsBeatStraight => Hand::SIZE..=7,
+            _ => Hand::SIZE..=Hand::SIZE,
+        }
+    }
+
+    /// Lowest rank of the deck these rules are played with.
+    fn lowest_rank(self) -> Rank {
+        match self {
+            Rules::ShortDeck | Rules::ShortDeckTripsBeatStraight => Rank::Six,
+            _ => Rank::Two,
+        }
+    }
+
+    /// Strength of exactly five cards.
+    ///
+    /// Only [`Rules::High`] plays jokers as wild cards; the other rules have none and fail on a
+    /// joker with [`ParseError::Joker`], its offset that of the cards written out.
+    pub fn strength(self, hand: &Hand) -> Result<Strength, ParseError> {
+        let cards = hand.cards();
+        match (cards.iter()).position(|a| a.is_joker() && !self.has_wild_cards()) {
+            Some(i) => Err(ParseError::Joker {
+                offset: written_offset(cards, i),
+            }),
+            None => Ok(self.score(hand)),
+        }
+    }
+
+    /// [`Rules::strength`] without the check for jokers.
+    fn score(self, hand: &Hand) -> Strength {
+        match self {
+            Rules::High => Strength::High(hand.evaluate()),
+            Rules::AceToFive | Rules::Razz => {
+                Strength::AceToFive(LowValue::ace_to_five(hand.cards()))
+            }
+            Rules::DeuceToSeven => Strength::DeuceToSeven(Reverse(hand.compute(false))),
+            Rules::ShortDeck | Rules::ShortDeckTripsBeatStraight => {
+                let value = hand.compute_below(Some(self.lowest_rank()));
+                let place = match (value.category(), self) {
+                    (Hands::Flush, _) => Hands::FullHouse as u8,
+                    (Hands::FullHouse, _) => Hands::Flush as u8,
+                    (Hands::ThreeOfAKind, Rules::ShortDeckTripsBeatStraight) => {
+                        Hands::Straight as u8
+                    }
+                    (Hands::Straight, Rules::ShortDeckTripsBeatStraight) => {
+                        Hands::ThreeOfAKind as u8
+                    }
+                    (category, _) => category as u8,
+                };
+                Strength::ShortDeck(place, value)
+            }
+        }
+    }
+
+    /// Strength of the best five of `cards`, which must hold at least five distinct cards.
+    pub(crate) fn best_strength(self, cards: &[Card]) -> Strength {
+        cards
+            .iter()
+            .copied()
+            .combinations(Hand::SIZE)
+            .map(|a| self.score(&Hand::from_cards(a)))
+            .max()
+            .expect("at least five cards")
+    }
+
+    /// Whether jokers play as wild cards; without them there is nothing for a joker to be.
+    fn has_wild_cards(self) -> bool {
+        self == Rules::High
+    }
+
+    /// Fail on the first joker in a game without wild cards, or the first card below the deck's
+    /// lowest rank, `value` being the text `cards` came from.
+    fn check_cards(self, value: &str, cards: &[Card]) -> Result<(), ParseError> {
+        let offset = |i: usize| value.split(' ').take(i).map(|a| a.len() + 1).sum();
+        if let Some(i) = (cards.iter()).position(|a| a.is_joker() && !self.has_wild_cards()) {
+            return Err(ParseError::Joker { offset: offset(i) });
+        }
+        let lowest = self.lowest_rank();
+        let Some(i) = (cards.iter()).position(|a| !a.is_joker() && a.rank() < lowest) else {
+            return Ok(());
+        };
+        Err(ParseError::BadRank {
+            offset: offset(i),
+            rank: cards[i].rank().to_string(),
+        })
+    }
+
+    /// Compare two hands, `Greater` meaning `a` beats `b`.
+    ///
+    /// Fails as [`Rules::strength`] does, the error in `a` tagged with index 0 and in `b` with 1.
+    pub fn compare(self, a: &Hand, b: &Hand) -> Result<std::cmp::Ordering, ParseError> {
+        let a = self.strength(a).map_err(|e| e.in_hand(0))?;
+        let b = self.strength(b).map_err(|e| e.in_hand(1))?;
+        Ok(a.cmp(&b))
+    }
+}
+
+/// Like [`crate::try_winning_hands`], but ranking the hands under `rules`.
+///
+/// Hands hold five cards, or five to seven under [`Rules::Razz`]. Jokers are wild under
+/// [`Rules::High`] and rejected with [`ParseError::Joker`] by every other rule set.
+///
+/// ```
+/// use poker::rules::{winning_hands_with, Rules};
+///
+/// let hands = ["AS 2H 3C 4D 5H", "2D 3D 4S 5C 7H"];
+/// assert_eq!(winning_hands_with(Rules::AceToFive, &hands), Ok(vec!["AS 2H 3C 4D 5H"]));
+/// assert_eq!(winning_hands_with(Rules::DeuceToSeven, &hands), Ok(vec!["2D 3D 4S 5C 7H"]));
+/// ```
+pub fn winning_hands_with<'a>(
+    rules: Rules,
+    hands_strs: &[&'a str],
+) -> Result<Vec<&'a str>, ParseError> {
+    let values = hands_strs
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            parse_cards(a)
+                .and_then(|cards| {
+                    ParseError::check_count(rules.hand_sizes(), cards.len())?;
+                    rules.check_cards(a, &cards)?;
+                    Ok(rules.best_strength(&cards))
+                })
+                .map_err(|e| e.in_hand(i))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(best_indices(&values)
+        .into_iter()
+        .map(|i| hands_strs[i])
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ace_to_five_ignores_straights_and_flushes() {
+        assert_eq!(
+            winning_hands_with(Rules::AceToFive, &["AH 2H 3H 4H 5H", "AS 2D 3C 4D 6H"]),
+            Ok(vec!["AH 2H 3H 4H 5H"])
+        );
+        assert_eq!(
+            winning_hands_with(Rules::AceToFive, &["AH AD 2C 3H 4S", "KS QD JC 9H 8D"]),
+            Ok(vec!["KS QD JC 9H 8D"])
+        );
+        assert_eq!(
+            winning_hands_with(Rules::AceToFive, &["AH 2S JK 4C 7H"]),
+            Err(ParseError::Joker { offset: 6 }.in_hand(0))
+        );
+    }
+
+    #[test]
+    fn deuce_to_seven_counts_straights_and_flushes() {
+        assert_eq!(
+            winning_hands_with(Rules::DeuceToSeven, &["2H 3H 4H 5H 7H", "2S 3D 4C 5D 8H"]),
+            Ok(vec!["2S 3D 4C 5D 8H"])
+        );
+        assert_eq!(
+            winning_hands_with(Rules::DeuceToSeven, &["3S 4D 5C 6D 7H", "2S 3D 4C 5D 9H"]),
+            Ok(vec!["2S 3D 4C 5D 9H"])
+        );
+        // the ace is high, so A-2-3-4-5 is merely ace high and loses to king high
+        assert_eq!(
+            winning_hands_with(Rules::DeuceToSeven, &["AS 2D 3C 4D 5H", "KS 2H 3D 4S 6C"]),
+            Ok(vec!["KS 2H 3D 4S 6C"])
+        );
+        // no wild cards: a joker is not an ace-high hand
+        assert_eq!(
+            winning_hands_with(Rules::DeuceToSeven, &["JK 2S 3D 4C 7H"]),
+            Err(ParseError::Joker { offset: 0 }.in_hand(0))
+        );
+    }
+
+    #[test]
+    fn razz_plays_best_five_of_seven() {
+        assert_eq!(
+            winning_hands_with(
+                Rules::Razz,
+                &["KS KD 2C 3H 4S 6D 7C", "AS AD 2H 3D 5C 9S QS"]
+            ),
+            Ok(vec!["KS KD 2C 3H 4S 6D 7C"])
+        );
+        assert!(winning_hands_with(Rules::AceToFive, &["KS KD 2C 3H 4S 6D 7C"]).is_err());
+        assert_eq!(
+            winning_hands_with(Rules::Razz, &["KS 2D 3C 4H 5S", "KS KD 2C 3H 4S 6D JK"]),
+            Err(ParseError::Joker { offset: 18 }.in_hand(1))
+        );
+    }
+
+    #[test]
+    fn jokers_only_play_under_high_rules() {
+        let wild: Hand = "JK 2S 3D 4C 6H".parse().unwrap();
+        let six: Hand = "AD 2H 3S 4D 6C".parse().unwrap();
+        for rules in [
+            Rules::AceToFive,
+            Rules::DeuceToSeven,
+            Rules::Razz,
+            Rules::ShortDeck,
+        ] {
+            assert_eq!(rules.strength(&wild), Err(ParseError::Joker { offset: 0 }));
+            assert_eq!(
+                rules.compare(&six, &wild),
+                Err(ParseError::Joker { offset: 0 }.in_hand(1))
+            );
+        }
+        assert_eq!(
+            Rules::High.compare(&wild, &six),
+            Ok(std::cmp::Ordering::Greater)
+        );
+    }
+
+    #[test]
+    fn short_deck() {
+        let rules = Rules::ShortDeck;
+        // A-6-7-8-9 is a straight, nine high, below 6-7-8-9-10
+        let wheel: Hand = "AS 6H 7C 8D 9H".parse().unwrap();
+        assert!(matches!(
+            rules.strength(&wheel),
+            Ok(Strength::ShortDeck(_, ref a)) if a.category() == Hands::Straight && a.ranks()[0] == Rank::Nine
+        ));
+        assert_eq!(
+            winning_hands_with(rules, &["AS 6H 7C 8D 9H", "6S 7H 8C 9D 10H"]),
+            Ok(vec!["6S 7H 8C 9D 10H"])
+        );
+        // a flush beats a full house
+        let hands = ["6H 8H 10H QH KH", "AS AD AC KS KD"];
+        assert_eq!(winning_hands_with(rules, &hands), Ok(vec![hands[0]]));
+        assert_eq!(winning_hands_with(Rules::High, &hands), Ok(vec![hands[1]]));
+        // the best five of seven play
+        assert_eq!(
+            winning_hands_with(rules, &["AS 6H 7C 8D 9H KS KD", "QS QD QC JS 10D 7S 6D"]),
+            Ok(vec!["AS 6H 7C 8D 9H KS KD"])
+        );
+        assert_eq!(
+            winning_hands_with(rules, &["AS 6H 7C 8D 5H"]),
+            Err(ParseError::BadRank {
+                offset: 12,
+                rank: "5".to_string()
+            }
+            .in_hand(0))
+        );
+        assert_eq!(
+            winning_hands_with(rules, &["9S JK 6D 7C 8H"]),
+            Err(ParseError::Joker { offset: 3 }.in_hand(0))
+        );
+    }
+
+    #[test]
+    fn short_deck_trips_beat_straight() {
+        let hands = ["AS 6H 7C 8D 9H", "QS QD QC JS 10D"];
+        assert_eq!(
+            winning_hands_with(Rules::ShortDeck, &hands),
+            Ok(vec![hands[0]])
+        );
+        assert_eq!(
+            winning_hands_with(Rules::ShortDeckTripsBeatStraight, &hands),
+            Ok(vec![hands[1]])
+        );
+        // the other swaps still hold
+        let hands = ["6H 8H 10H QH KH", "AS AD AC KS KD", "QS QD QC JS 10D"];
+        assert_eq!(
+            winning_hands_with(Rules::ShortDeckTripsBeatStraight, &hands),
+            Ok(vec![hands[0]])
+        );
+        assert_eq!(
+            winning_hands_with(Rules::ShortDeckTripsBeatStraight, &["QS QD JK JS 10D"]),
+            Err(ParseError::Joker { offset: 6 }.in_hand(0))
+        );
+    }
+
+    #[test]
+    fn high_matches_winning_hands() {
+        let hands = ["4S 5H 4C 8D 4H", "10D JH QS KD AC", "3S 3H 2S 3D 3C"];
+        assert_eq!(
+            winning_hands_with(Rules::High, &hands),
+            crate::try_winning_hands(&hands)
+        );
+    }
+}
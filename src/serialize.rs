@@ -0,0 +1,282 @@
+//! Serde support, behind the `serde` feature.
+//!
+//! Cards serialize in a compact string form, rank then lowercase suit, e.g. `"Ah"`, `"Td"`
+//! or `"JK"` for a joker. Ranks and suits on their own serialize as `"A"`, `"T"` and `"h"`,
+//! and a [`Hand`] as a sequence of its cards. Deserializing accepts every [`Notation`]. See [`structured`] for the `{"rank":"A","suit":"h"}` form.
+
+use crate::{Card, Hand, HandValue, Hands, Notation, ParseError, Rank, Suit};
+use itertools::Itertools;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+fn deserialize_str<'de, D: Deserializer<'de>, T: std::str::FromStr<Err = ParseError>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(de::Error::custom)
+}
+
+impl Serialize for Rank {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.display(Notation::Short))
+    }
+}
+
+impl<'de> Deserialize<'de> for Rank {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_str(deserializer)
+    }
+}
+
+/// A joker's suit serializes as `"*"`.
+impl Serialize for Suit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.display(Notation::Short))
+    }
+}
+
+impl<'de> Deserialize<'de> for Suit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        if value == "*" {
+            return Ok(Suit::Joker);
+        }
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.display(Notation::Short))
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_str(deserializer)
+    }
+}
+
+impl Serialize for Hand {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.cards())
+    }
+}
+
+/// Fails unless there are exactly five distinct cards, as [`Hand::new`].
+impl<'de> Deserialize<'de> for Hand {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Hand::new(Vec::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+/// A [`HandValue`] as written, checked before it becomes one.
+#[derive(Deserialize)]
+pub(crate) struct RawHandValue {
+    category: Hands,
+    ranks: Vec<Rank>,
+}
+
+/// Fails unless the ranks are ones the evaluator could give the category, e.g. two distinct
+/// ranks for a full house or five running ranks for a straight; partial hands may have fewer.
+impl TryFrom<RawHandValue> for HandValue {
+    type Error = String;
+
+    fn try_from(raw: RawHandValue) -> Result<Self, Self::Error> {
+        let RawHandValue { category, ranks } = raw;
+        let descending = |a: &[Rank]| a.windows(2).all(|a| a[0] > a[1]);
+        let running = |a: &[Rank]| a.windows(2).all(|a| a[0].value() == a[1].value() + 1);
+        let valid = match category {
+            Hands::StraightFlush | Hands::Straight => {
+                ranks.len() == Hand::SIZE
+                    && (running(&ranks) || (ranks[4] == Rank::Ace && running(&ranks[..4])))
+            }
+            Hands::Flush => ranks.len() == Hand::SIZE && descending(&ranks),
+            _ => {
+                // ranks of the pairs, trips or quads come first, then the kickers, high to low
+                let (groups, sizes) = match category {
+                    Hands::FiveOfAKind => (1, 1..=1),
+                    Hands::FourOfAKind => (1, 1..=2),
+                    Hands::FullHouse => (2, 2..=2),
+                    Hands::ThreeOfAKind => (1, 1..=3),
+                    Hands::TwoPair => (2, 2..=3),
+                    Hands::OnePair => (1, 1..=4),
+                    _ => (0, 1..=5),
+                };
+                sizes.contains(&ranks.len())
+                    && ranks.iter().all_unique()
+                    && descending(&ranks[groups..])
+                    && (category != Hands::TwoPair || descending(&ranks[..2]))
+            }
+        };
+        if valid {
+            Ok(HandValue::new(category, ranks))
+        } else {
+            Err(format!(
+                "ranks {} cannot make {category}",
+                ranks.iter().join(" ")
+            ))
+        }
+    }
+}
+
+/// The structured form of a card, `{"rank":"A","suit":"h"}`, for use with `#[serde(with)]`.
+///
+/// A joker is `{"rank":"A","suit":"*"}`, its rank being meaningless.
+///
+/// ```
+/// # use poker::{structured, Card, Hand};
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct Showdown {
+///     #[serde(with = "structured")]
+///     top: Card,
+///     #[serde(with = "structured::hand")]
+///     hand: Hand,
+/// }
+/// ```
+pub mod structured {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Structured {
+        rank: Rank,
+        suit: Suit,
+    }
+
+    impl From<&Card> for Structured {
+        fn from(card: &Card) -> Self {
+            Self {
+                rank: card.rank(),
+                suit: card.suit(),
+            }
+        }
+    }
+
+    impl From<Structured> for Card {
+        fn from(card: Structured) -> Self {
+            match card.suit {
+                Suit::Joker => Card::joker(),
+                suit => Card::new(card.rank, suit),
+            }
+        }
+    }
+
+    pub fn serialize<S: Serializer>(card: &Card, serializer: S) -> Result<S::Ok, S::Error> {
+        Structured::from(card).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Card, D::Error> {
+        Structured::deserialize(deserializer).map(Card::from)
+    }
+
+    /// A [`Hand`] as a sequence of structured cards.
+    pub mod hand {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(hand: &Hand, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(hand.cards().iter().map(Structured::from))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Hand, D::Error> {
+            let cards = Vec::<Structured>::deserialize(deserializer)?;
+            Hand::new(cards.into_iter().map(Card::from).collect()).map_err(de::Error::custom)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HandValue, Hands};
+
+    #[test]
+    fn compact_round_trip() {
+        let hand = "10H JK 2C QS 10D".parse::<Hand>().unwrap();
+        let json = serde_json::to_string(&hand).unwrap();
+        assert_eq!(json, r#"["Th","JK","2c","Qs","Td"]"#);
+        let back: Hand = serde_json::from_str(&json).unwrap();
+        assert_eq!(format!("{:?}", back.cards()), format!("{:?}", hand.cards()));
+        assert_eq!(serde_json::to_string(&Rank::Ten).unwrap(), r#""T""#);
+        assert_eq!(serde_json::to_string(&Suit::Club).unwrap(), r#""c""#);
+        let card: Card = serde_json::from_str(r#""10S""#).unwrap();
+        assert_eq!(card.to_string(), "10S");
+    }
+
+    #[test]
+    fn structured_round_trip() {
+        #[derive(Serialize, Deserialize)]
+        struct Row {
+            #[serde(with = "structured")]
+            card: Card,
+            #[serde(with = "structured::hand")]
+            hand: Hand,
+        }
+        let row = Row {
+            card: "AH".parse().unwrap(),
+            hand: "2S 3S 4S 5S JK".parse().unwrap(),
+        };
+        let json = serde_json::to_string(&row).unwrap();
+        assert!(
+            json.starts_with(r#"{"card":{"rank":"A","suit":"h"},"hand":[{"rank":"2","suit":"s"}"#)
+        );
+        let back: Row = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.card.to_string(), "AH");
+        assert_eq!(
+            format!("{:?}", back.hand.cards()),
+            format!("{:?}", row.hand.cards())
+        );
+    }
+
+    #[test]
+    fn invalid_input_is_rejected() {
+        assert!(serde_json::from_str::<Card>(r#""1h""#).is_err());
+        assert!(serde_json::from_str::<Hand>(r#"["Ah","Ah","2c","3c","4c"]"#).is_err());
+        assert!(serde_json::from_str::<Hand>(r#"["Ah","Kh"]"#).is_err());
+    }
+
+    #[test]
+    fn evaluation_results() {
+        let value = "5H 8S 5D 8D 5C".parse::<Hand>().unwrap().evaluate();
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, r#"{"category":"full_house","ranks":["5","8"]}"#);
+        assert_eq!(serde_json::from_str::<HandValue>(&json).unwrap(), value);
+        assert_eq!(
+            serde_json::from_str::<Hands>(r#""two_pair""#).unwrap(),
+            Hands::TwoPair
+        );
+    }
+
+    #[test]
+    fn hand_values_are_checked() {
+        let values = [
+            "AS 2D 3C 4H 5S",
+            "6S 7S 8S 9S 10S",
+            "AS JS 8S 4S 2S",
+            "9C 9D 9H 9S JK",
+            "KS KD 2C 2H 7S",
+            "QS 9D 5C 3H 2S",
+        ]
+        .map(|a| a.parse::<Hand>().unwrap().evaluate());
+        let partial = crate::evaluate_partial(&crate::card::parse_cards("KS KD 4C").unwrap());
+        for value in values.into_iter().chain([partial.unwrap()]) {
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(serde_json::from_str::<HandValue>(&json).unwrap(), value);
+        }
+        for json in [
+            r#"{"category":"full_house","ranks":[]}"#,
+            r#"{"category":"full_house","ranks":["5","5"]}"#,
+            r#"{"category":"flush","ranks":["A","A","K","Q","3"]}"#,
+            r#"{"category":"straight","ranks":["A","K","Q","J","9"]}"#,
+            r#"{"category":"one_pair","ranks":["K","2","7"]}"#,
+            r#"{"category":"high_card","ranks":["K","Q","J","9","7","2"]}"#,
+        ] {
+            assert!(serde_json::from_str::<HandValue>(json).is_err(), "{json}");
+        }
+        let error = serde_json::from_str::<HandValue>(r#"{"category":"full_house","ranks":[]}"#);
+        assert!(error
+            .unwrap_err()
+            .to_string()
+            .contains("cannot make full house"));
+    }
+}
@@ -0,0 +1,324 @@
+//! Seven-card stud and Stud Hi-Lo: two down cards and one up card, three more up cards,
+//! and a last card down, each player making the best five of their own seven.
+//!
+//! [`Stud`] deals the streets and tells who brings in and who acts first; the betting
+//! itself is left to the caller, who folds players out with [`Stud::fold`].
+//!
+//! ```
+//! use poker::{deck::Deck, stud::{Street, Stud}};
+//!
+//! let deck: Deck = "AS QH 2H KD QS 9C 7C 3D 5S 4C 3C 6D".parse().unwrap();
+//! let mut stud = Stud::new(3, deck).unwrap();
+//! // up cards 7C, 3D and 5S: the three of diamonds brings in
+//! assert_eq!(stud.bring_in(), 1);
+//! stud.deal_next().unwrap();
+//! assert_eq!(stud.street(), Street::Fourth);
+//! // 7C 4C, 3D 3C and 5S 6D: the pair of threes on board acts first
+//! assert_eq!(stud.first_to_act(), 1);
+//! ```
+
+use crate::{
+    deck::Deck,
+    evaluate_partial,
+    low::{self, LowRule},
+    Card, DeckError, HandValue,
+};
+
+/// Betting round, named after the number of cards each player holds.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Street {
+    Third,
+    Fourth,
+    Fifth,
+    Sixth,
+    Seventh,
+}
+
+/// One player's cards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seat {
+    /// Cards only the player sees, in the order dealt.
+    pub down: Vec<Card>,
+    /// Cards dealt face up, in the order dealt.
+    pub up: Vec<Card>,
+    pub folded: bool,
+}
+
+/// Who wins at showdown, by seat.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Showdown {
+    /// Seats sharing the high half, or the whole pot when nobody wins low.
+    pub high: Vec<usize>,
+    /// Seats sharing the low half; empty in high-only stud or when no low qualifies.
+    pub low: Vec<usize>,
+    /// Fraction of the pot won by each seat.
+    pub shares: Vec<f64>,
+}
+
+/// A hand of seven-card stud being dealt.
+#[derive(Debug, Clone)]
+pub struct Stud {
+    seats: Vec<Seat>,
+    deck: Deck,
+    street: Street,
+    /// A last card dealt face up to everyone, when too few cards are left to deal seventh street.
+    community: Option<Card>,
+}
+
+impl Stud {
+    /// Deal third street to `players` players, a card at a time round the table from seat 0.
+    ///
+    /// # Panics
+    ///
+    /// Panics with fewer than two players.
+    pub fn new(players: usize, mut deck: Deck) -> Result<Self, DeckError> {
+        assert!(players >= 2, "stud needs at least two players");
+        let mut seats = (0..players)
+            .map(|_| Seat {
+                down: vec![],
+                up: vec![],
+                folded: false,
+            })
+            .collect::<Vec<_>>();
+        for round in 0..3 {
+            for seat in &mut seats {
+                let card = deck.burn()?;
+                if round < 2 {
+                    seat.down.push(card);
+                } else {
+                    seat.up.push(card);
+                }
+            }
+        }
+        Ok(Self {
+            seats,
+            deck,
+            street: Street::Third,
+            community: None,
+        })
+    }
+
+    pub fn seats(&self) -> &[Seat] {
+        &self.seats
+    }
+
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    /// The card shared by everyone on seventh street, if the deck ran short.
+    pub fn community(&self) -> Option<Card> {
+        self.community
+    }
+
+    /// Deal the next street to every player still in: an up card on fourth to sixth street and a
+    /// down card on seventh. If the deck cannot give everyone a seventh card, one card is dealt
+    /// face up in the middle for all to share.
+    ///
+    /// # Panics
+    ///
+    /// Panics if seventh street has already been dealt.
+    pub fn deal_next(&mut self) -> Result<(), DeckError> {
+        let street = match self.street {
+            Street::Third => Street::Fourth,
+            Street::Fourth => Street::Fifth,
+            Street::Fifth => Street::Sixth,
+            Street::Sixth => Street::Seventh,
+            Street::Seventh => panic!("every street has been dealt"),
+        };
+        let live = self.seats.iter().filter(|a| !a.folded).count();
+        if street == Street::Seventh && self.deck.len() < live {
+            self.community = Some(self.deck.burn()?);
+        } else {
+            let mut cards = self.deck.deal(live)?.into_iter();
+            for seat in self.seats.iter_mut().filter(|a| !a.folded) {
+                let card = cards.next().expect("one card per live seat");
+                if street == Street::Seventh {
+                    seat.down.push(card);
+                } else {
+                    seat.up.push(card);
+                }
+            }
+        }
+        self.street = street;
+        Ok(())
+    }
+
+    /// The player folds, taking no more cards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such seat.
+    pub fn fold(&mut self, seat: usize) {
+        self.seats[seat].folded = true;
+    }
+
+    /// The seat forced to bring in on third street: the lowest up card, aces high,
+    /// ties going to the lowest suit in bridge order, clubs first.
+    pub fn bring_in(&self) -> usize {
+        (0..self.seats.len())
+            .filter(|&i| !self.seats[i].folded)
+            .min_by_key(|&i| self.seats[i].up[0])
+            .expect("a player still in")
+    }
+
+    /// The seat that opens the betting: the bring-in on third street, and afterwards the best
+    /// hand showing, judged by pairs, trips and quads alone. Tied boards go to the lowest seat.
+    pub fn first_to_act(&self) -> usize {
+        if self.street == Street::Third {
+            return self.bring_in();
+        }
+        let showing = |i: usize| -> HandValue {
+            evaluate_partial(&self.seats[i].up).expect("one to four distinct up cards")
+        };
+        (0..self.seats.len())
+            .filter(|&i| !self.seats[i].folded)
+            .rev()
+            .max_by_key(|&i| showing(i))
+            .expect("a player still in")
+    }
+
+    /// Every card the seat plays with: down cards, up cards and the community card if there is one.
+    pub fn cards(&self, seat: usize) -> Vec<Card> {
+        let seat = &self.seats[seat];
+        (seat.down.iter().chain(&seat.up).chain(&self.community))
+            .copied()
+            .collect()
+    }
+
+    /// Winners among the players still in, each playing their best five cards; with `low_rule`
+    /// the pot is split with the best low as in Stud Hi-Lo.
+    ///
+    /// A player left alone wins everything, whatever the street.
+    ///
+    /// # Panics
+    ///
+    /// Panics if several players are still in before seventh street is dealt.
+    pub fn showdown(&self, low_rule: Option<LowRule>) -> Showdown {
+        let live = (0..self.seats.len())
+            .filter(|&i| !self.seats[i].folded)
+            .collect::<Vec<_>>();
+        let (high, low) = match live[..] {
+            [only] => (vec![only], vec![]),
+            _ => {
+                assert_eq!(self.street, Street::Seventh, "the hand is not over");
+                let cards = live.iter().map(|&i| self.cards(i)).collect::<Vec<_>>();
+                let (high, low) = low::split(&cards, low_rule);
+                let seats = |a: Vec<usize>| a.into_iter().map(|i| live[i]).collect::<Vec<_>>();
+                (seats(high), seats(low))
+            }
+        };
+        Showdown {
+            shares: low::shares(self.seats.len(), &high, &low),
+            high,
+            low,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Seven cards each for three players, dealt in stud order.
+    fn three_players() -> Stud {
+        // a card to each seat in turn: down, down, up, three more up and the last down
+        let deck = "AS QH 2H KD QS 9C 7C 3D 5S 4C 3C 6D 10S KH 2C 7S 6H 6C 4S AH 9S"
+            .parse()
+            .unwrap();
+        Stud::new(3, deck).unwrap()
+    }
+
+    fn deal_all(stud: &mut Stud) {
+        while stud.street() < Street::Seventh {
+            stud.deal_next().unwrap();
+        }
+    }
+
+    #[test]
+    fn dealing_the_streets() {
+        let mut stud = three_players();
+        assert_eq!(stud.seats()[0].down.len(), 2);
+        assert_eq!(stud.seats()[0].up, ["7C".parse().unwrap()]);
+        deal_all(&mut stud);
+        for seat in stud.seats() {
+            assert_eq!((seat.down.len(), seat.up.len()), (3, 4));
+        }
+        assert_eq!(stud.seats()[2].down[2], "9S".parse().unwrap());
+        assert_eq!(stud.community(), None);
+    }
+
+    #[test]
+    fn bring_in_breaks_ties_by_suit() {
+        let deck = "AS KD 7C QH QS 2D 2H 9C 2C".parse().unwrap();
+        let stud = Stud::new(3, deck).unwrap();
+        // deuces of hearts and clubs showing: clubs is the lower suit
+        assert_eq!(stud.bring_in(), 2);
+    }
+
+    #[test]
+    fn best_board_acts_first() {
+        let mut stud = three_players();
+        stud.deal_next().unwrap();
+        // 7C 4C, 3D 3C, 5S 6D
+        assert_eq!(stud.first_to_act(), 1);
+        stud.deal_next().unwrap();
+        // 7C 4C 10S, 3D 3C KH, 5S 6D 2C: the pair still leads
+        assert_eq!(stud.first_to_act(), 1);
+        stud.fold(1);
+        stud.deal_next().unwrap();
+        // 7C 4C 10S 7S against 5S 6D 2C 6H: sevens beat sixes
+        assert_eq!(stud.first_to_act(), 0);
+    }
+
+    #[test]
+    fn tied_boards_go_to_the_lowest_seat() {
+        let deck = "AS KD QH QS 9D 8C 2C 8D".parse().unwrap();
+        let mut stud = Stud::new(2, deck).unwrap();
+        stud.deal_next().unwrap();
+        // 9D 2C against 8C 8D
+        assert_eq!(stud.first_to_act(), 1);
+        let deck = "AS KD QH QS 9C 9D 8D 8C".parse().unwrap();
+        let mut stud = Stud::new(2, deck).unwrap();
+        stud.deal_next().unwrap();
+        // 9C 8D against 9D 8C: suits do not count after third street
+        assert_eq!(stud.first_to_act(), 0);
+    }
+
+    #[test]
+    fn showdown_high_and_hi_lo() {
+        let mut stud = three_players();
+        deal_all(&mut stud);
+        // seat 0: AS KD 7C 4C 10S 7S 4S, seat 1: QH QS 3D 3C KH 6H AH, seat 2: 2H 9C 5S 6D 2C 6C 9S
+        let result = stud.showdown(None);
+        assert_eq!(result.high, [1]);
+        assert_eq!(result.shares, [0.0, 1.0, 0.0]);
+        let result = stud.showdown(Some(LowRule::EightOrBetter));
+        assert_eq!(result.low, Vec::<usize>::new());
+        let result = stud.showdown(Some(LowRule::AnyLow));
+        assert_eq!((result.high, result.low), (vec![1], vec![0]));
+    }
+
+    #[test]
+    fn last_player_standing() {
+        let mut stud = three_players();
+        stud.fold(0);
+        stud.fold(2);
+        let result = stud.showdown(Some(LowRule::EightOrBetter));
+        assert_eq!(result.high, [1]);
+        assert_eq!(result.shares, [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn community_card_when_the_deck_runs_short() {
+        let mut deck = Deck::new();
+        deck.shuffle_seeded(3);
+        let mut stud = Stud::new(8, deck).unwrap();
+        deal_all(&mut stud);
+        // 8 × 6 = 48 cards before seventh street leaves 4 for 8 players
+        assert!(stud.community().is_some());
+        assert_eq!(stud.cards(7).len(), 7);
+        let total = stud.showdown(None).shares.iter().sum::<f64>();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+}
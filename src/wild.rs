@@ -0,0 +1,207 @@
+//! Wild cards: jokers, and optionally whole ranks or single cards such as one-eyed jacks.
+
+use crate::{best_indices, card::parse_cards, Card, Hand, HandValue, ParseError, Rank, Suit};
+use itertools::Itertools;
+
+/// Which cards are wild. Jokers always are.
+///
+/// ```
+/// use poker::{wild::Wilds, Card, Rank, Suit};
+///
+/// let wilds = Wilds::deuces().with_card(Card::new(Rank::Jack, Suit::Spade));
+/// assert!(wilds.is_wild(&"2H".parse().unwrap()));
+/// assert!(wilds.is_wild(&"JS".parse().unwrap()));
+/// assert!(!wilds.is_wild(&"JD".parse().unwrap()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Wilds {
+    ranks: Vec<Rank>,
+    cards: Vec<Card>,
+}
+
+impl Wilds {
+    /// Only jokers are wild.
+    pub fn jokers() -> Self {
+        Self::default()
+    }
+
+    /// Jokers and every deuce are wild.
+    pub fn deuces() -> Self {
+        Self::default().with_rank(Rank::Two)
+    }
+
+    /// Jokers and the jacks drawn in profile, of spades and hearts, are wild.
+    pub fn one_eyed_jacks() -> Self {
+        Self::default()
+            .with_card(Card::new(Rank::Jack, Suit::Spade))
+            .with_card(Card::new(Rank::Jack, Suit::Heart))
+    }
+
+    /// Also make every card of `rank` wild.
+    pub fn with_rank(mut self, rank: Rank) -> Self {
+        self.ranks.push(rank);
+        self
+    }
+
+    /// Also make this single card wild.
+    pub fn with_card(mut self, card: Card) -> Self {
+        self.cards.push(card);
+        self
+    }
+
+    pub fn is_wild(&self, card: &Card) -> bool {
+        card.is_joker()
+            || self.ranks.contains(&card.rank())
+            || self.cards.iter().any(|a| a.is_same(card))
+    }
+}
+
+/// Value of five cards, or fewer as in [`crate::evaluate_partial`], when every wild card
+/// stands for whichever card makes the best hand.
+///
+/// Wild cards may take the rank of natural cards, which is how five of a kind arises, but a
+/// flush can only be filled with cards of its suit that are not already in the hand.
+pub fn evaluate(cards: &[Card], wilds: &Wilds) -> HandValue {
+    let (wild, natural): (Vec<_>, Vec<_>) = cards.iter().copied().partition(|a| wilds.is_wild(a));
+    if wild.is_empty() {
+        return Hand::from_cards(natural).compute(true);
+    }
+    if natural.is_empty() && cards.len() == Hand::SIZE {
+        return HandValue::new(crate::Hands::FiveOfAKind, vec![Rank::Ace]);
+    }
+
+    // Only flushes care about suits: trying the naturals' suit, when they share one,
+    // and one other suit covers every distinct outcome.
+    let first = natural.first().map_or(Suit::Club, Card::suit);
+    let other = Suit::ALL.into_iter().find(|&a| a != first).unwrap();
+    let suits = if natural.iter().all(|a| a.suit() == first) {
+        vec![first, other]
+    } else {
+        vec![first]
+    };
+    let candidates = suits
+        .into_iter()
+        .cartesian_product(Rank::ALL)
+        .map(|(suit, rank)| Card::new(rank, suit))
+        .collect::<Vec<_>>();
+
+    candidates
+        .into_iter()
+        .combinations_with_replacement(wild.len())
+        .map(|a| [natural.as_slice(), &a].concat())
+        // a flush holding a card twice is no flush; the same ranks in another suit count instead
+        .filter(|a| !a.iter().map(Card::suit).all_equal() || a.iter().all_unique())
+        .map(|a| Hand::from_cards(a).compute(true))
+        .max()
+        .unwrap()
+}
+
+/// Like [`crate::try_winning_hands`], but with `wilds` wild.
+///
+/// ```
+/// use poker::wild::{winning_hands, Wilds};
+///
+/// let hands = ["AS AH AD AC 2S", "KS KH KD KC JK"];
+/// assert_eq!(winning_hands(&Wilds::deuces(), &hands), Ok(vec!["AS AH AD AC 2S"]));
+/// ```
+pub fn winning_hands<'a>(
+    wilds: &Wilds,
+    hands_strs: &[&'a str],
+) -> Result<Vec<&'a str>, ParseError> {
+    let values = hands_strs
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            parse_cards(a)
+                .and_then(|cards| {
+                    ParseError::check_count(Hand::SIZE..=Hand::SIZE, cards.len())?;
+                    Ok(evaluate(&cards, wilds))
+                })
+                .map_err(|e| e.in_hand(i))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(best_indices(&values)
+        .into_iter()
+        .map(|i| hands_strs[i])
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hands;
+
+    fn value(hand: &str, wilds: &Wilds) -> HandValue {
+        evaluate(&parse_cards(hand).unwrap(), wilds)
+    }
+
+    #[test]
+    fn joker_parsing() {
+        let hand: Hand = "JK XX AS AD AC".parse().unwrap();
+        assert_eq!(hand.cards().iter().filter(|a| a.is_joker()).count(), 2);
+        assert_eq!(hand.cards()[0].to_string(), "JK");
+        assert_eq!(hand.evaluate().category(), Hands::FiveOfAKind);
+        assert_eq!(hand.evaluate().ranks(), [Rank::Ace]);
+    }
+
+    #[test]
+    fn five_of_a_kind_beats_straight_flush() {
+        assert_eq!(
+            crate::winning_hands(&["10S JS QS KS AS", "9H 9D 9C 9S JK"]),
+            ["9H 9D 9C 9S JK"]
+        );
+        // tie-break on the rank of the five of a kind
+        assert_eq!(
+            winning_hands(&Wilds::deuces(), &["9H 9D 9C 9S 2S", "10H 10D 2C 10S JK"]),
+            Ok(vec!["10H 10D 2C 10S JK"])
+        );
+    }
+
+    #[test]
+    fn wilds_make_the_best_hand() {
+        let deuces = Wilds::deuces();
+        assert_eq!(
+            value("2S 5H 6H 7H 8H", &deuces).category(),
+            Hands::StraightFlush
+        );
+        assert_eq!(value("2S 5H 6D 7H 8H", &deuces).ranks()[0], Rank::Nine);
+        assert_eq!(
+            value("2S 2H 3D 3H KH", &deuces).category(),
+            Hands::FourOfAKind
+        );
+        assert_eq!(
+            value("2S 2H 2D 2C JK", &deuces).category(),
+            Hands::FiveOfAKind
+        );
+        assert_eq!(value("2S 4H 6D 8H 10C", &deuces).category(), Hands::OnePair);
+        assert_eq!(value("2S 4H 6H 8H 10H", &deuces).category(), Hands::Flush);
+        assert_eq!(value("2S 4H 6H 8H 10H", &deuces).ranks()[0], Rank::Ace);
+    }
+
+    #[test]
+    fn wild_flush_cards_are_not_in_the_hand() {
+        // the joker is the jack of hearts, not a second ace of hearts
+        let flush = value("AH KH QH 3H JK", &Wilds::jokers());
+        assert_eq!(flush.category(), Hands::Flush);
+        assert_eq!(
+            flush.ranks(),
+            [Rank::Ace, Rank::King, Rank::Queen, Rank::Jack, Rank::Three]
+        );
+        assert_eq!(
+            winning_hands(&Wilds::jokers(), &["AH KH QH 3H JK", "AD KD QD JD 9D"]),
+            Ok(vec!["AD KD QD JD 9D"])
+        );
+        // two wild cards fill a flush with two different cards
+        let flush = value("AH KH QH JK XX", &Wilds::jokers());
+        assert_eq!(flush.category(), Hands::StraightFlush);
+        let flush = value("AH KH 9H 3H 2S", &Wilds::deuces());
+        assert_eq!(flush.ranks()[..3], [Rank::Ace, Rank::King, Rank::Queen]);
+    }
+
+    #[test]
+    fn one_eyed_jacks() {
+        let wilds = Wilds::one_eyed_jacks();
+        assert_eq!(value("JS QD QC 4H 4D", &wilds).category(), Hands::FullHouse);
+        assert_eq!(value("JD QD QC 4H 4D", &wilds).category(), Hands::TwoPair);
+    }
+}
@@ -0,0 +1,102 @@
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+fn poker(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_poker"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    assert!(output.status.success(), "{output:?}");
+    std::str::from_utf8(&output.stdout).unwrap().trim_end()
+}
+
+#[test]
+fn rank_prints_description_and_category() {
+    let output = poker(&["rank", "5H 8S 5D 8D 5C"], "");
+    assert_eq!(
+        stdout(&output),
+        "Full house, fives over eights (full house)"
+    );
+}
+
+#[test]
+fn rank_json() {
+    let output = poker(&["--json", "rank", "5H 8S 5D 8D 5C"], "");
+    assert_eq!(
+        stdout(&output),
+        r#"{"hand":"5H 8S 5D 8D 5C","category":"full house","description":"Full house, fives over eights","ranks":["5","8"]}"#
+    );
+}
+
+#[test]
+fn winners_reads_stdin() {
+    let input = "4S 5H 4C 8D 4H\n\n3S 4S 5D 6H JH\n4H 5C 4D 8S 4S\n";
+    let output = poker(&["winners"], input);
+    assert_eq!(stdout(&output), "4S 5H 4C 8D 4H\n4H 5C 4D 8S 4S");
+    let output = poker(&["winners", "--json"], input);
+    assert_eq!(
+        stdout(&output),
+        r#"{"winners":["4S 5H 4C 8D 4H","4H 5C 4D 8S 4S"]}"#
+    );
+}
+
+#[test]
+fn winners_reports_line_of_bad_hand() {
+    let output = poker(&["winners"], "4S 5H 4C 8D 4H\n\n3S 4S 5D 6H\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr).trim_end(),
+        "error: line 3: expected 5 cards, found 4"
+    );
+}
+
+#[test]
+fn equity_on_flop_is_exhaustive() {
+    let output = poker(&["equity", "--board", "QH 7H 2C", "AH KH", "QS QD"], "");
+    let out = stdout(&output);
+    assert!(out.ends_with("exhaustive over 990 runouts"), "{out}");
+    let output = poker(
+        &["--json", "equity", "--board", "QH 7H 2C", "AH KH", "QS QD"],
+        "",
+    );
+    assert!(stdout(&output)
+        .starts_with(r#"{"exhaustive":true,"trials":990,"players":[{"hole":"AH KH","#));
+}
+
+#[test]
+fn usage_errors_exit_with_2() {
+    assert_eq!(poker(&[], "").status.code(), Some(2));
+    assert_eq!(poker(&["equity", "AH AD"], "").status.code(), Some(2));
+    assert_eq!(poker(&["rank"], "").status.code(), Some(2));
+    let output = poker(
+        &["--json", "equity", "--samples", "0", "AH AD", "KS KC"],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn equity_rejects_jokers() {
+    let output = poker(&["equity", "--board", "JK 2C 3D", "AH AD", "KS KC"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr).trim_end(),
+        "error: board: joker at byte 0 in a game without wild cards"
+    );
+}